@@ -0,0 +1,85 @@
+//! Compares hashing a header from scratch with looking it up in a `HeaderHashCache`, and
+//! reports the memory each cache entry spends on its key.
+//!
+//! Run with `cargo bench --bench header_hash`.
+
+use ethereum::{Header, HeaderHashCache, PartialHeader};
+use ethereum_types::{Bloom, H160, H256, H64, U256};
+use std::hint::black_box;
+use std::mem::size_of;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200_000;
+
+fn header(number: u64) -> Header {
+	Header::new(
+		PartialHeader {
+			parent_hash: H256::repeat_byte(1),
+			beneficiary: H160::repeat_byte(2),
+			state_root: H256::repeat_byte(3),
+			receipts_root: H256::repeat_byte(4),
+			logs_bloom: Bloom::repeat_byte(5),
+			difficulty: U256::zero(),
+			number: number.into(),
+			gas_limit: 30_000_000.into(),
+			gas_used: 12_345_678.into(),
+			timestamp: 1_710_338_135,
+			extra_data: vec![6; 32],
+			mix_hash: H256::repeat_byte(7),
+			nonce: H64::zero(),
+			base_fee: Some(7_000_000_000u64.into()),
+			blob_gas_used: Some(393_216.into()),
+			excess_blob_gas: Some(0.into()),
+			parent_beacon_block_root: Some(H256::repeat_byte(8)),
+			requests_hash: None,
+		},
+		H256::repeat_byte(9),
+		H256::repeat_byte(10),
+	)
+}
+
+fn measure(name: &str, mut f: impl FnMut(u32) -> H256) -> Duration {
+	for i in 0..ITERATIONS / 10 {
+		black_box(f(i));
+	}
+	let start = Instant::now();
+	for i in 0..ITERATIONS {
+		black_box(f(i));
+	}
+	let per_iteration = start.elapsed() / ITERATIONS;
+	println!("{name:<24} {per_iteration:>10?}");
+	per_iteration
+}
+
+fn main() {
+	let mut header = header(19_426_587);
+	header.withdrawals_root = Some(H256::repeat_byte(11));
+	let headers = [header.clone(), {
+		let mut other = header.clone();
+		other.number += U256::one();
+		other
+	}];
+
+	let compute = measure("compute_hash", |_| black_box(&header).compute_hash());
+
+	let cache = HeaderHashCache::new(NonZeroUsize::new(16).unwrap());
+	let hit = measure("cache hit", |_| cache.hash(black_box(&header)));
+
+	// Two headers alternating in a cache of one entry miss on every lookup.
+	let thrashing = HeaderHashCache::new(NonZeroUsize::new(1).unwrap());
+	measure("cache miss", |i| {
+		thrashing.hash(black_box(&headers[i as usize % 2]))
+	});
+
+	println!(
+		"a hit is {:.1}x faster than computing the hash",
+		compute.as_secs_f64() / hit.as_secs_f64()
+	);
+
+	// The cache keys on the header itself; compare with keying on its RLP encoding.
+	let header_key = size_of::<Header>() + header.extra_data.capacity();
+	let encoded_key = size_of::<Vec<u8>>() + rlp::encode(&header).len();
+	println!("{:<24} {:>7} bytes", "key: header", header_key);
+	println!("{:<24} {:>7} bytes", "key: RLP encoding", encoded_key);
+}
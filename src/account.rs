@@ -1,4 +1,11 @@
-use ethereum_types::{H256, U256};
+use crate::{
+	util::{verify_proof, KeccakHasher, ProofError},
+	Bytes,
+};
+use alloc::{boxed::Box, vec::Vec};
+use ethereum_types::{Address, H256, U256};
+use hash_db::Hasher;
+use rlp::DecoderError;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[derive(rlp::RlpEncodable, rlp::RlpDecodable)]
@@ -10,3 +17,368 @@ pub struct Account {
 	pub storage_root: H256,
 	pub code_hash: H256,
 }
+
+/// Claimed value of a storage slot with its proof, as in the `storageProof` entries of an
+/// `eth_getProof` response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "with-codec", derive(codec::Encode, codec::Decode))]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StorageProof {
+	pub key: H256,
+	pub value: U256,
+	pub proof: Vec<Bytes>,
+}
+
+/// Account and storage slots proven against a state root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedAccount {
+	pub address: Address,
+	/// `None` if the proof shows that the account does not exist.
+	pub account: Option<Account>,
+	/// Proven value of each requested slot, in request order.
+	pub storage: Vec<(H256, U256)>,
+}
+
+/// Reason an `eth_getProof` response failed verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountProofError {
+	/// The account proof does not resolve against the state root.
+	AccountProof(ProofError),
+	/// The proven account is not a valid RLP-encoded [`Account`].
+	InvalidAccount(DecoderError),
+	/// The proven account differs from the claimed one. `proven` is `None` if the account
+	/// does not exist.
+	AccountMismatch { proven: Option<Box<Account>> },
+	/// The proof of slot `key` does not resolve against the account's storage root.
+	StorageProof { key: H256, error: ProofError },
+	/// The proven value of slot `key` is not a valid RLP-encoded integer.
+	InvalidStorageValue { key: H256, error: DecoderError },
+	/// The proven value of slot `key` differs from the claimed one.
+	StorageValueMismatch {
+		key: H256,
+		claimed: U256,
+		proven: U256,
+	},
+}
+
+/// Verify an `eth_getProof` response for `address` against `state_root`. `claimed` holds the
+/// response's `nonce`, `balance`, `storageHash` and `codeHash`.
+pub fn verify_account_proof(
+	state_root: H256,
+	address: Address,
+	claimed: &Account,
+	account_proof: &[Bytes],
+	storage_proofs: &[StorageProof],
+) -> Result<VerifiedAccount, AccountProofError> {
+	let account = verify_proof(
+		state_root,
+		KeccakHasher::hash(address.as_bytes()).as_bytes(),
+		account_proof,
+	)
+	.map_err(AccountProofError::AccountProof)?
+	.map(|encoded| rlp::decode::<Account>(&encoded))
+	.transpose()
+	.map_err(AccountProofError::InvalidAccount)?;
+
+	if !matches_claim(account.as_ref(), claimed) {
+		return Err(AccountProofError::AccountMismatch {
+			proven: account.map(Box::new),
+		});
+	}
+
+	let storage_root = account
+		.as_ref()
+		.map_or(KeccakHasher::hash(&rlp::NULL_RLP), |a| a.storage_root);
+
+	let storage = storage_proofs
+		.iter()
+		.map(|slot| {
+			let proven = verify_storage_proof(storage_root, slot)?;
+			if proven != slot.value {
+				return Err(AccountProofError::StorageValueMismatch {
+					key: slot.key,
+					claimed: slot.value,
+					proven,
+				});
+			}
+			Ok((slot.key, proven))
+		})
+		.collect::<Result<_, _>>()?;
+
+	Ok(VerifiedAccount {
+		address,
+		account,
+		storage,
+	})
+}
+
+/// Whether `claimed` describes the proven account. Nodes report a missing account with zero
+/// nonce and balance, and with either zero or empty storage and code hashes.
+fn matches_claim(proven: Option<&Account>, claimed: &Account) -> bool {
+	match proven {
+		Some(account) => account == claimed,
+		None => {
+			claimed.nonce.is_zero()
+				&& claimed.balance.is_zero()
+				&& (claimed.storage_root.is_zero()
+					|| claimed.storage_root == KeccakHasher::hash(&rlp::NULL_RLP))
+				&& (claimed.code_hash.is_zero() || claimed.code_hash == KeccakHasher::hash(&[]))
+		}
+	}
+}
+
+fn verify_storage_proof(
+	storage_root: H256,
+	slot: &StorageProof,
+) -> Result<U256, AccountProofError> {
+	// Nodes commonly return an empty proof for accounts without storage.
+	if slot.proof.is_empty() && storage_root == KeccakHasher::hash(&rlp::NULL_RLP) {
+		return Ok(U256::zero());
+	}
+
+	let value = verify_proof(
+		storage_root,
+		KeccakHasher::hash(slot.key.as_bytes()).as_bytes(),
+		&slot.proof,
+	)
+	.map_err(|error| AccountProofError::StorageProof {
+		key: slot.key,
+		error,
+	})?;
+
+	match value {
+		Some(encoded) => {
+			rlp::decode(&encoded).map_err(|error| AccountProofError::InvalidStorageValue {
+				key: slot.key,
+				error,
+			})
+		}
+		None => Ok(U256::zero()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::MemoryTrie;
+	use alloc::vec;
+
+	fn secure_insert(trie: &mut MemoryTrie, key: &[u8], value: Vec<u8>) {
+		trie.insert(KeccakHasher::hash(key).as_bytes(), value);
+	}
+
+	#[test]
+	fn verifies_account_and_storage_proofs() {
+		let mut storage = MemoryTrie::new();
+		for slot in 0u8..8 {
+			let value = U256::from(slot) * 1000 + 1;
+			secure_insert(
+				&mut storage,
+				H256::from_low_u64_be(slot.into()).as_bytes(),
+				rlp::encode(&value).to_vec(),
+			);
+		}
+
+		let address = Address::repeat_byte(0xb1);
+		let account = Account {
+			nonce: 1.into(),
+			balance: 5_000.into(),
+			storage_root: storage.root(),
+			code_hash: KeccakHasher::hash(&[]),
+		};
+		let mut state = MemoryTrie::new();
+		secure_insert(
+			&mut state,
+			address.as_bytes(),
+			rlp::encode(&account).to_vec(),
+		);
+		for i in 0u8..16 {
+			let other = Account {
+				balance: i.into(),
+				..account.clone()
+			};
+			secure_insert(
+				&mut state,
+				Address::repeat_byte(i).as_bytes(),
+				rlp::encode(&other).to_vec(),
+			);
+		}
+
+		let slot_proof = |slot: u64, value: u64| {
+			let key = H256::from_low_u64_be(slot);
+			StorageProof {
+				key,
+				value: value.into(),
+				proof: storage.prove(KeccakHasher::hash(key.as_bytes()).as_bytes()),
+			}
+		};
+		let account_proof = state.prove(KeccakHasher::hash(address.as_bytes()).as_bytes());
+
+		let verified = verify_account_proof(
+			state.root(),
+			address,
+			&account,
+			&account_proof,
+			&[slot_proof(3, 3001), slot_proof(100, 0)],
+		)
+		.unwrap();
+		assert_eq!(verified.account, Some(account.clone()));
+		assert_eq!(
+			verified.storage,
+			vec![
+				(H256::from_low_u64_be(3), 3001.into()),
+				(H256::from_low_u64_be(100), 0.into())
+			]
+		);
+
+		assert_eq!(
+			verify_account_proof(
+				state.root(),
+				address,
+				&account,
+				&account_proof,
+				&[slot_proof(3, 1)]
+			),
+			Err(AccountProofError::StorageValueMismatch {
+				key: H256::from_low_u64_be(3),
+				claimed: 1.into(),
+				proven: 3001.into(),
+			})
+		);
+		assert_eq!(
+			verify_account_proof(H256::zero(), address, &account, &account_proof, &[]),
+			Err(AccountProofError::AccountProof(ProofError::HashMismatch))
+		);
+		for claimed in [
+			Account {
+				nonce: 2.into(),
+				..account.clone()
+			},
+			Account {
+				balance: 1.into(),
+				..account.clone()
+			},
+			Account {
+				storage_root: H256::zero(),
+				..account.clone()
+			},
+			Account {
+				code_hash: H256::repeat_byte(0xc0),
+				..account.clone()
+			},
+		] {
+			assert_eq!(
+				verify_account_proof(state.root(), address, &claimed, &account_proof, &[]),
+				Err(AccountProofError::AccountMismatch {
+					proven: Some(Box::new(account.clone())),
+				})
+			);
+		}
+
+		let missing = Address::repeat_byte(0xee);
+		let missing_proof = state.prove(KeccakHasher::hash(missing.as_bytes()).as_bytes());
+		let empty = Account {
+			nonce: 0.into(),
+			balance: 0.into(),
+			storage_root: KeccakHasher::hash(&rlp::NULL_RLP),
+			code_hash: H256::zero(),
+		};
+		let verified = verify_account_proof(
+			state.root(),
+			missing,
+			&empty,
+			&missing_proof,
+			&[StorageProof {
+				key: H256::zero(),
+				value: 0.into(),
+				proof: vec![],
+			}],
+		)
+		.unwrap();
+		assert_eq!(verified.account, None);
+		assert_eq!(verified.storage, vec![(H256::zero(), 0.into())]);
+		assert_eq!(
+			verify_account_proof(state.root(), missing, &account, &missing_proof, &[]),
+			Err(AccountProofError::AccountMismatch { proven: None })
+		);
+	}
+
+	#[cfg(feature = "with-serde")]
+	#[test]
+	fn verifies_node_proof_response() {
+		use crate::serde_helpers::bytes::decode_hex;
+		use serde_json::json;
+
+		// `eth_getProof` at block 0 of geth's `customg` test genesis: one account holding one
+		// storage slot. Geth pins the genesis hash 0x89c99d90..., which commits to this state root.
+		let state_root = H256(hex_literal::hex!(
+			"8257aee1cdaa2c42ae5dfad636710ee73437466b7504770feb19d63e2be3c913"
+		));
+		let account_leaf = "0xf86aa120f22bb46edf31af855938befaa870ed3d86a4ad93a9ecf7c63ceaa80daea9ac4db846f8448001a02e7c74fd29ada2a30b8609059629d0c19c5be3685aa8e82e5cd6f94921c2d90da0c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+		let storage_leaf = "0xf844a12048078cfed56339ea54962e72c37c7f588fc4f8e5bc173827ba75cb10a63a96a5a1a00100000000000000000000000000000000000000000000000000000000000000";
+		let response = json!({
+			"address": "0x0100000000000000000000000000000000000000",
+			"accountProof": [account_leaf],
+			"balance": "0x1",
+			"codeHash": "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+			"nonce": "0x0",
+			"storageHash": "0x2e7c74fd29ada2a30b8609059629d0c19c5be3685aa8e82e5cd6f94921c2d90d",
+			"storageProof": [
+				{
+					"key": "0x0100000000000000000000000000000000000000000000000000000000000000",
+					"value": "0x100000000000000000000000000000000000000000000000000000000000000",
+					"proof": [storage_leaf]
+				},
+				{
+					"key": "0x0000000000000000000000000000000000000000000000000000000000000002",
+					"value": "0x0",
+					"proof": [storage_leaf]
+				}
+			]
+		});
+
+		let field = |name: &str| response[name].clone();
+		let proof = |nodes: &serde_json::Value| -> Vec<Bytes> {
+			nodes
+				.as_array()
+				.unwrap()
+				.iter()
+				.map(|node| decode_hex::<serde_json::Error>(node.as_str().unwrap()).unwrap())
+				.collect()
+		};
+		let address: Address = serde_json::from_value(field("address")).unwrap();
+		let claimed = Account {
+			nonce: serde_json::from_value(field("nonce")).unwrap(),
+			balance: serde_json::from_value(field("balance")).unwrap(),
+			storage_root: serde_json::from_value(field("storageHash")).unwrap(),
+			code_hash: serde_json::from_value(field("codeHash")).unwrap(),
+		};
+		let storage_proofs: Vec<StorageProof> = response["storageProof"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.map(|slot| StorageProof {
+				key: serde_json::from_value(slot["key"].clone()).unwrap(),
+				value: serde_json::from_value(slot["value"].clone()).unwrap(),
+				proof: proof(&slot["proof"]),
+			})
+			.collect();
+
+		let verified = verify_account_proof(
+			state_root,
+			address,
+			&claimed,
+			&proof(&response["accountProof"]),
+			&storage_proofs,
+		)
+		.unwrap();
+		assert_eq!(verified.account, Some(claimed));
+		assert_eq!(
+			verified.storage,
+			vec![
+				(storage_proofs[0].key, U256::one() << 248),
+				(H256::from_low_u64_be(2), U256::zero())
+			]
+		);
+	}
+}
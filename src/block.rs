@@ -1,8 +1,13 @@
 use crate::{
-	util::ordered_trie_root, EnvelopedDecodable, EnvelopedEncodable, Header, PartialHeader,
-	TransactionAny, TransactionV0, TransactionV1, TransactionV2,
+	util::ordered_trie_root, DecodeError, EnvelopedDecodable, EnvelopedDecoderError,
+	EnvelopedEncodable, Header, PartialHeader, TransactionAny, TransactionV0, TransactionV1,
+	TransactionV2, TransactionV3, TransactionV4, Withdrawal,
 };
-use alloc::vec::Vec;
+#[cfg(feature = "secp256k1")]
+use crate::{RecoverableTransaction, RecoveryError};
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "secp256k1")]
+use ethereum_types::Address;
 use ethereum_types::H256;
 use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 use sha3::{Digest, Keccak256};
@@ -17,11 +22,12 @@ pub struct Block<T> {
 	pub header: Header,
 	pub transactions: Vec<T>,
 	pub ommers: Vec<Header>,
+	pub withdrawals: Option<Vec<Withdrawal>>,
 }
 
 impl<T: EnvelopedEncodable> Encodable for Block<T> {
 	fn rlp_append(&self, s: &mut RlpStream) {
-		s.begin_list(3);
+		s.begin_list(if self.withdrawals.is_some() { 4 } else { 3 });
 		s.append(&self.header);
 		s.append_list::<Vec<u8>, _>(
 			&self
@@ -31,47 +37,180 @@ impl<T: EnvelopedEncodable> Encodable for Block<T> {
 				.collect::<Vec<_>>(),
 		);
 		s.append_list(&self.ommers);
+		if let Some(withdrawals) = &self.withdrawals {
+			s.append_list(withdrawals);
+		}
 	}
 }
 
-impl<T: EnvelopedDecodable> Decodable for Block<T> {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+impl<T> Block<T>
+where
+	T: EnvelopedDecodable,
+	DecodeError: From<EnvelopedDecoderError<T::PayloadDecoderError>>,
+{
+	/// Decode an RLP-encoded block, reporting which field or transaction failed to decode.
+	pub fn decode_rlp(rlp: &Rlp) -> Result<Self, DecodeError> {
+		let item_count = rlp.item_count()?;
+		if item_count != 3 && item_count != 4 {
+			return Err(DecoderError::RlpIncorrectListLen.into());
+		}
+
 		Ok(Self {
-			header: rlp.val_at(0)?,
+			header: rlp.val_at(0).map_err(DecodeError::field("header"))?,
 			transactions: rlp
-				.list_at::<Vec<u8>>(1)?
+				.list_at::<Vec<u8>>(1)
+				.map_err(DecodeError::field("transactions"))?
 				.into_iter()
-				.map(|raw_tx| {
-					EnvelopedDecodable::decode(&raw_tx)
-						.map_err(|_| DecoderError::Custom("decode enveloped transaction failed"))
+				.enumerate()
+				.map(|(index, raw_tx)| {
+					EnvelopedDecodable::decode(&raw_tx).map_err(|e| DecodeError::Transaction {
+						index,
+						error: Box::new(e.into()),
+					})
 				})
 				.collect::<Result<Vec<_>, _>>()?,
-			ommers: rlp.list_at(2)?,
+			ommers: rlp.list_at(2).map_err(DecodeError::field("ommers"))?,
+			withdrawals: if item_count == 4 {
+				Some(rlp.list_at(3).map_err(DecodeError::field("withdrawals"))?)
+			} else {
+				None
+			},
 		})
 	}
 }
 
+impl<T> Decodable for Block<T>
+where
+	T: EnvelopedDecodable,
+	DecodeError: From<EnvelopedDecoderError<T::PayloadDecoderError>>,
+{
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Self::decode_rlp(rlp).map_err(Into::into)
+	}
+}
+
+/// Mismatch between a block header and the body it was delivered with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlockBodyError {
+	/// `ommers_hash` does not match the ommers in the body.
+	OmmersHashMismatch { expected: H256, actual: H256 },
+	/// `transactions_root` does not match the transactions in the body.
+	TransactionsRootMismatch { expected: H256, actual: H256 },
+	/// `withdrawals_root` does not match the withdrawals in the body, or only one of the
+	/// header and the body carries withdrawals.
+	WithdrawalsRootMismatch {
+		expected: Option<H256>,
+		actual: Option<H256>,
+	},
+}
+
+fn ommers_hash(ommers: &[Header]) -> H256 {
+	H256::from_slice(Keccak256::digest(&rlp::encode_list(ommers)[..]).as_slice())
+}
+
+fn transactions_root<T: EnvelopedEncodable>(transactions: &[T]) -> H256 {
+	ordered_trie_root(
+		transactions
+			.iter()
+			.map(|r| EnvelopedEncodable::encode(r).freeze()),
+	)
+}
+
+fn withdrawals_root(withdrawals: &[Withdrawal]) -> H256 {
+	ordered_trie_root(withdrawals.iter().map(rlp::encode))
+}
+
 impl<T: EnvelopedEncodable> Block<T> {
-	pub fn new(partial_header: PartialHeader, transactions: Vec<T>, ommers: Vec<Header>) -> Self {
-		let ommers_hash =
-			H256::from_slice(Keccak256::digest(&rlp::encode_list(&ommers)[..]).as_slice());
-		let transactions_root = ordered_trie_root(
-			transactions
-				.iter()
-				.map(|r| EnvelopedEncodable::encode(r).freeze()),
+	/// Build a block from its parts. A header carrying any Cancun or later field belongs to a
+	/// post-Shanghai block, so missing `withdrawals` are then taken to be empty.
+	pub fn new(
+		partial_header: PartialHeader,
+		transactions: Vec<T>,
+		ommers: Vec<Header>,
+		withdrawals: Option<Vec<Withdrawal>>,
+	) -> Self {
+		let post_shanghai = partial_header.blob_gas_used.is_some()
+			|| partial_header.excess_blob_gas.is_some()
+			|| partial_header.parent_beacon_block_root.is_some()
+			|| partial_header.requests_hash.is_some();
+		let withdrawals = withdrawals.or_else(|| post_shanghai.then(Vec::new));
+
+		let mut header = Header::new(
+			partial_header,
+			ommers_hash(&ommers),
+			transactions_root(&transactions),
 		);
+		header.withdrawals_root = withdrawals.as_deref().map(withdrawals_root);
 
 		Self {
-			header: Header::new(partial_header, ommers_hash, transactions_root),
+			header,
 			transactions,
 			ommers,
+			withdrawals,
+		}
+	}
+
+	/// Check that the ommers hash, transactions root and withdrawals root in the header match
+	/// the block body.
+	pub fn verify_body(&self) -> Result<(), BlockBodyError> {
+		let actual = ommers_hash(&self.ommers);
+		if actual != self.header.ommers_hash {
+			return Err(BlockBodyError::OmmersHashMismatch {
+				expected: self.header.ommers_hash,
+				actual,
+			});
+		}
+
+		let actual = transactions_root(&self.transactions);
+		if actual != self.header.transactions_root {
+			return Err(BlockBodyError::TransactionsRootMismatch {
+				expected: self.header.transactions_root,
+				actual,
+			});
 		}
+
+		let actual = self.withdrawals.as_deref().map(withdrawals_root);
+		if actual != self.header.withdrawals_root {
+			return Err(BlockBodyError::WithdrawalsRootMismatch {
+				expected: self.header.withdrawals_root,
+				actual,
+			});
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(feature = "secp256k1")]
+impl<T: RecoverableTransaction> Block<T> {
+	/// Recover the sender of every transaction, in the same order as `transactions`.
+	pub fn recover_senders(&self) -> Vec<Result<Address, RecoveryError>> {
+		self.transactions
+			.iter()
+			.map(RecoverableTransaction::recover_signer)
+			.collect()
+	}
+}
+
+#[cfg(feature = "rayon")]
+impl<T: RecoverableTransaction + Sync> Block<T> {
+	/// Recover the sender of every transaction in parallel, in the same order as
+	/// `transactions`.
+	pub fn par_recover_senders(&self) -> Vec<Result<Address, RecoveryError>> {
+		use rayon::prelude::*;
+
+		self.transactions
+			.par_iter()
+			.map(RecoverableTransaction::recover_signer)
+			.collect()
 	}
 }
 
 pub type BlockV0 = Block<TransactionV0>;
 pub type BlockV1 = Block<TransactionV1>;
 pub type BlockV2 = Block<TransactionV2>;
+pub type BlockV3 = Block<TransactionV3>;
+pub type BlockV4 = Block<TransactionV4>;
 pub type BlockAny = Block<TransactionAny>;
 
 impl<T> From<BlockV0> for Block<T>
@@ -83,6 +222,7 @@ where
 			header: t.header,
 			transactions: t.transactions.into_iter().map(|t| t.into()).collect(),
 			ommers: t.ommers,
+			withdrawals: t.withdrawals,
 		}
 	}
 }
@@ -93,6 +233,339 @@ impl From<BlockV1> for BlockV2 {
 			header: t.header,
 			transactions: t.transactions.into_iter().map(|t| t.into()).collect(),
 			ommers: t.ommers,
+			withdrawals: t.withdrawals,
 		}
 	}
 }
+
+impl From<BlockV2> for BlockV3 {
+	fn from(t: BlockV2) -> Self {
+		Self {
+			header: t.header,
+			transactions: t.transactions.into_iter().map(|t| t.into()).collect(),
+			ommers: t.ommers,
+			withdrawals: t.withdrawals,
+		}
+	}
+}
+
+impl From<BlockV3> for BlockV4 {
+	fn from(t: BlockV3) -> Self {
+		Self {
+			header: t.header,
+			transactions: t.transactions.into_iter().map(|t| t.into()).collect(),
+			ommers: t.ommers,
+			withdrawals: t.withdrawals,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{util::MemoryTrie, LegacyTransaction};
+	#[cfg(feature = "secp256k1")]
+	use crate::{
+		EIP1559TransactionMessage, LegacyTransactionMessage, LocalSigner, RecoveryError, Signer,
+		TransactionAction,
+	};
+	use alloc::vec;
+	use ethereum_types::{Bloom, H160, H64, U256};
+	use hex_literal::hex;
+
+	fn partial_header() -> PartialHeader {
+		PartialHeader {
+			parent_hash: H256::zero(),
+			beneficiary: H160::zero(),
+			state_root: H256::zero(),
+			receipts_root: H256::zero(),
+			logs_bloom: Bloom::zero(),
+			difficulty: U256::zero(),
+			number: U256::zero(),
+			gas_limit: U256::zero(),
+			gas_used: U256::zero(),
+			timestamp: 0,
+			extra_data: vec![],
+			mix_hash: H256::zero(),
+			nonce: H64::zero(),
+			base_fee: None,
+			blob_gas_used: None,
+			excess_blob_gas: None,
+			parent_beacon_block_root: None,
+			requests_hash: None,
+		}
+	}
+
+	#[test]
+	fn decode_reports_failing_transaction_index() {
+		let block = BlockV2::new(partial_header(), vec![], vec![], None);
+		let mut stream = RlpStream::new_list(3);
+		stream.append(&block.header);
+		stream.append_list::<Vec<u8>, _>(&[vec![0x02, 0xc0], vec![0x05, 0xc0]]);
+		stream.append_list(&block.ommers);
+
+		assert_eq!(
+			BlockV2::decode_rlp(&Rlp::new(&stream.out())),
+			Err(DecodeError::Transaction {
+				index: 0,
+				error: Box::new(DecodeError::Rlp(DecoderError::RlpIncorrectListLen)),
+			})
+		);
+
+		let mut stream = RlpStream::new_list(3);
+		stream.append(&block.header);
+		stream.append_list::<Vec<u8>, _>(&[vec![0x05, 0xc0]]);
+		stream.append_list(&block.ommers);
+
+		assert_eq!(
+			BlockV2::decode_rlp(&Rlp::new(&stream.out())),
+			Err(DecodeError::Transaction {
+				index: 0,
+				error: Box::new(DecodeError::UnknownTypeId(0x05)),
+			})
+		);
+	}
+
+	/// Mainnet block 1, which has an empty body.
+	fn mainnet_block_1() -> BlockV2 {
+		let block = BlockV2::new(
+			PartialHeader {
+				parent_hash: hex!(
+					"d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+				)
+				.into(),
+				beneficiary: hex!("05a56e2d52c817161883f50c441c3228cfe54d9f").into(),
+				state_root: hex!(
+					"d67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3"
+				)
+				.into(),
+				receipts_root: hex!(
+					"56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+				)
+				.into(),
+				logs_bloom: Bloom::zero(),
+				difficulty: 0x3_ff80_0000_u64.into(),
+				number: 1.into(),
+				gas_limit: 5000.into(),
+				gas_used: 0.into(),
+				timestamp: 0x55ba_4224,
+				extra_data: b"Geth/v1.0.0/linux/go1.4.2".to_vec(),
+				mix_hash: hex!("969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59")
+					.into(),
+				nonce: hex!("539bd4979fef1ec4").into(),
+				base_fee: None,
+				blob_gas_used: None,
+				excess_blob_gas: None,
+				parent_beacon_block_root: None,
+				requests_hash: None,
+			},
+			vec![],
+			vec![],
+			None,
+		);
+		assert_eq!(
+			block.header.compute_hash(),
+			H256::from(hex!(
+				"88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6"
+			))
+		);
+		block
+	}
+
+	#[test]
+	fn computes_withdrawals_root_and_round_trips_body() {
+		let empty = BlockV2::new(partial_header(), vec![], vec![], Some(vec![]));
+		assert_eq!(
+			empty.header.withdrawals_root,
+			Some(H256::from(hex!(
+				"56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+			)))
+		);
+
+		let mut partial = partial_header();
+		partial.base_fee = Some(7.into());
+		let withdrawals = vec![
+			Withdrawal {
+				index: 0,
+				validator_index: 65_535,
+				address: H160::repeat_byte(0x11),
+				amount: 32_000_000_000,
+			},
+			Withdrawal {
+				index: 1,
+				validator_index: 65_536,
+				address: H160::repeat_byte(0x22),
+				amount: 1,
+			},
+		];
+		// EIP-4895 encodes a withdrawal as `[index, validator_index, address, amount]`.
+		let encoded = [
+			[
+				&hex!("df8082ffff94")[..],
+				&[0x11; 20],
+				&hex!("850773594000"),
+			]
+			.concat(),
+			[&hex!("db018301000094")[..], &[0x22; 20], &hex!("01")].concat(),
+		];
+		for (withdrawal, encoded) in withdrawals.iter().zip(&encoded) {
+			assert_eq!(rlp::encode(withdrawal).to_vec(), *encoded);
+		}
+
+		// The root is that of a trie keyed by the RLP-encoded list index.
+		let mut trie = MemoryTrie::new();
+		trie.insert(&rlp::encode(&0u64), encoded[0].clone());
+		trie.insert(&rlp::encode(&1u64), encoded[1].clone());
+
+		let block = BlockV2::new(partial, vec![], vec![], Some(withdrawals));
+		assert_eq!(block.header.withdrawals_root, Some(trie.root()));
+		assert_eq!(
+			block.header.withdrawals_root,
+			Some(H256::from(hex!(
+				"6e34c80e1e19368d00d1d44481b0f87f278cff3ad675ae1d7a4c9575d17c89bd"
+			)))
+		);
+		assert_eq!(block.verify_body(), Ok(()));
+
+		let encoded = rlp::encode(&block);
+		assert_eq!(Rlp::new(&encoded).item_count(), Ok(4));
+		assert_eq!(rlp::decode::<BlockV2>(&encoded), Ok(block));
+	}
+
+	#[test]
+	fn derives_withdrawals_for_cancun_header() {
+		let mut partial = partial_header();
+		partial.base_fee = Some(7.into());
+		partial.blob_gas_used = Some(0.into());
+		partial.excess_blob_gas = Some(0.into());
+		partial.parent_beacon_block_root = Some(H256::zero());
+
+		let block = BlockV3::new(partial, vec![], vec![], None);
+		assert_eq!(block.withdrawals, Some(vec![]));
+		assert_eq!(block.header.withdrawals_root, Some(withdrawals_root(&[])));
+		assert_eq!(block.header.optional_field_gap(), None);
+		assert_eq!(block.verify_body(), Ok(()));
+
+		let encoded = rlp::encode(&block.header);
+		assert_eq!(Rlp::new(&encoded).item_count(), Ok(20));
+		assert_eq!(block.header.hash(), block.header.compute_hash());
+		assert_eq!(rlp::decode::<BlockV3>(&rlp::encode(&block)), Ok(block));
+	}
+
+	#[test]
+	fn verify_body_names_mismatched_root() {
+		let block = mainnet_block_1();
+		assert_eq!(block.verify_body(), Ok(()));
+
+		let mut tampered = block.clone();
+		tampered.ommers.push(block.header.clone());
+		assert_eq!(
+			tampered.verify_body(),
+			Err(BlockBodyError::OmmersHashMismatch {
+				expected: block.header.ommers_hash,
+				actual: ommers_hash(&tampered.ommers),
+			})
+		);
+
+		let mut tampered = block.clone();
+		tampered
+			.transactions
+			.push(TransactionV2::Legacy(LegacyTransaction {
+				nonce: 0.into(),
+				gas_price: 1.into(),
+				gas_limit: 21_000.into(),
+				action: crate::TransactionAction::Create,
+				value: 0.into(),
+				input: vec![],
+				signature: crate::TransactionSignature::new(
+					27,
+					H256::repeat_byte(1),
+					H256::repeat_byte(1),
+				)
+				.unwrap(),
+			}));
+		assert_eq!(
+			tampered.verify_body(),
+			Err(BlockBodyError::TransactionsRootMismatch {
+				expected: block.header.transactions_root,
+				actual: transactions_root(&tampered.transactions),
+			})
+		);
+
+		let mut tampered = block.clone();
+		tampered.withdrawals = Some(vec![]);
+		assert_eq!(
+			tampered.verify_body(),
+			Err(BlockBodyError::WithdrawalsRootMismatch {
+				expected: None,
+				actual: Some(withdrawals_root(&[])),
+			})
+		);
+
+		let mut shanghai = partial_header();
+		shanghai.base_fee = Some(7.into());
+		let block = BlockV2::new(shanghai, vec![], vec![], Some(vec![]));
+		let mut tampered = block.clone();
+		tampered.withdrawals = None;
+		assert_eq!(
+			tampered.verify_body(),
+			Err(BlockBodyError::WithdrawalsRootMismatch {
+				expected: block.header.withdrawals_root,
+				actual: None,
+			})
+		);
+	}
+
+	#[cfg(feature = "secp256k1")]
+	#[test]
+	fn recover_senders_reports_per_transaction_errors() {
+		let signer = LocalSigner::new(H256::repeat_byte(0x46)).unwrap();
+		let legacy = signer
+			.sign(LegacyTransactionMessage {
+				nonce: 0.into(),
+				gas_price: 1.into(),
+				gas_limit: 21_000.into(),
+				action: TransactionAction::Create,
+				value: 0.into(),
+				input: vec![],
+				chain_id: Some(1),
+			})
+			.unwrap();
+		let mut tampered = signer
+			.sign(EIP1559TransactionMessage {
+				chain_id: 1,
+				nonce: 1.into(),
+				max_priority_fee_per_gas: 1.into(),
+				max_fee_per_gas: 1.into(),
+				gas_limit: 21_000.into(),
+				action: TransactionAction::Create,
+				value: 0.into(),
+				input: vec![],
+				access_list: vec![],
+			})
+			.unwrap();
+		tampered.r = H256::zero();
+
+		let block = BlockV2::new(
+			partial_header(),
+			vec![
+				legacy.clone().into(),
+				TransactionV2::EIP1559(tampered),
+				legacy.into(),
+			],
+			vec![],
+			None,
+		);
+
+		assert_eq!(
+			block.recover_senders(),
+			vec![
+				Ok(signer.address()),
+				Err(RecoveryError::InvalidSignature),
+				Ok(signer.address()),
+			]
+		);
+		#[cfg(feature = "rayon")]
+		assert_eq!(block.par_recover_senders(), block.recover_senders());
+	}
+}
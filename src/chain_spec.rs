@@ -0,0 +1,233 @@
+use crate::Fork;
+use alloc::collections::BTreeMap;
+
+/// Point at which a fork activates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForkCondition {
+	/// Active from this block number on.
+	Block(u64),
+	/// Active from this block timestamp on.
+	Timestamp(u64),
+	/// Not scheduled.
+	Never,
+}
+
+impl ForkCondition {
+	/// Whether the condition holds for a block with the given number and timestamp.
+	pub fn is_active(&self, number: u64, timestamp: u64) -> bool {
+		match *self {
+			ForkCondition::Block(block) => number >= block,
+			ForkCondition::Timestamp(time) => timestamp >= time,
+			ForkCondition::Never => false,
+		}
+	}
+}
+
+/// Chain id and hardfork schedule of a chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainSpec {
+	pub chain_id: u64,
+	/// Activation of each scheduled fork. Frontier is always active; missing forks never are.
+	pub forks: BTreeMap<Fork, ForkCondition>,
+}
+
+impl ChainSpec {
+	/// Create a spec in which only Frontier rules apply.
+	pub fn new(chain_id: u64) -> Self {
+		Self {
+			chain_id,
+			forks: BTreeMap::new(),
+		}
+	}
+
+	/// Schedule `fork` to activate at `condition`.
+	pub fn with_fork(mut self, fork: Fork, condition: ForkCondition) -> Self {
+		self.forks.insert(fork, condition);
+		self
+	}
+
+	/// Ethereum mainnet.
+	pub fn mainnet() -> Self {
+		Self::new(1)
+			.with_fork(Fork::Homestead, ForkCondition::Block(1_150_000))
+			.with_fork(Fork::TangerineWhistle, ForkCondition::Block(2_463_000))
+			.with_fork(Fork::SpuriousDragon, ForkCondition::Block(2_675_000))
+			.with_fork(Fork::Byzantium, ForkCondition::Block(4_370_000))
+			.with_fork(Fork::Constantinople, ForkCondition::Block(7_280_000))
+			.with_fork(Fork::Petersburg, ForkCondition::Block(7_280_000))
+			.with_fork(Fork::Istanbul, ForkCondition::Block(9_069_000))
+			.with_fork(Fork::MuirGlacier, ForkCondition::Block(9_200_000))
+			.with_fork(Fork::Berlin, ForkCondition::Block(12_244_000))
+			.with_fork(Fork::London, ForkCondition::Block(12_965_000))
+			.with_fork(Fork::ArrowGlacier, ForkCondition::Block(13_773_000))
+			.with_fork(Fork::GrayGlacier, ForkCondition::Block(15_050_000))
+			.with_fork(Fork::Paris, ForkCondition::Block(15_537_394))
+			.with_fork(Fork::Shanghai, ForkCondition::Timestamp(1_681_338_455))
+			.with_fork(Fork::Cancun, ForkCondition::Timestamp(1_710_338_135))
+			.with_fork(Fork::Prague, ForkCondition::Timestamp(1_746_612_311))
+	}
+
+	/// Sepolia testnet.
+	pub fn sepolia() -> Self {
+		Self::new(11_155_111)
+			.with_forks_from_genesis_until(Fork::London)
+			.with_fork(Fork::Paris, ForkCondition::Block(1_450_409))
+			.with_fork(Fork::Shanghai, ForkCondition::Timestamp(1_677_557_088))
+			.with_fork(Fork::Cancun, ForkCondition::Timestamp(1_706_655_072))
+			.with_fork(Fork::Prague, ForkCondition::Timestamp(1_741_159_776))
+	}
+
+	/// Holesky testnet.
+	pub fn holesky() -> Self {
+		Self::new(17_000)
+			.with_forks_from_genesis_until(Fork::Paris)
+			.with_fork(Fork::Shanghai, ForkCondition::Timestamp(1_696_000_704))
+			.with_fork(Fork::Cancun, ForkCondition::Timestamp(1_707_305_664))
+			.with_fork(Fork::Prague, ForkCondition::Timestamp(1_740_434_112))
+	}
+
+	/// Activate every fork from Homestead up to and including `last` at genesis, skipping the
+	/// difficulty bomb delays.
+	fn with_forks_from_genesis_until(self, last: Fork) -> Self {
+		[
+			Fork::Homestead,
+			Fork::TangerineWhistle,
+			Fork::SpuriousDragon,
+			Fork::Byzantium,
+			Fork::Constantinople,
+			Fork::Petersburg,
+			Fork::Istanbul,
+			Fork::Berlin,
+			Fork::London,
+			Fork::Paris,
+		]
+		.iter()
+		.copied()
+		.filter(|fork| *fork <= last)
+		.fold(self, |spec, fork| {
+			spec.with_fork(fork, ForkCondition::Block(0))
+		})
+	}
+
+	/// Activation condition of `fork`.
+	pub fn fork_condition(&self, fork: Fork) -> ForkCondition {
+		match fork {
+			Fork::Frontier => ForkCondition::Block(0),
+			fork => self
+				.forks
+				.get(&fork)
+				.copied()
+				.unwrap_or(ForkCondition::Never),
+		}
+	}
+
+	/// Whether `fork` is active for a block with the given number and timestamp.
+	pub fn is_active(&self, fork: Fork, number: u64, timestamp: u64) -> bool {
+		self.fork_condition(fork).is_active(number, timestamp)
+	}
+
+	/// Latest fork active for a block with the given number and timestamp.
+	pub fn fork_at(&self, number: u64, timestamp: u64) -> Fork {
+		self.forks
+			.iter()
+			.rev()
+			.find(|(_, condition)| condition.is_active(number, timestamp))
+			.map_or(Fork::Frontier, |(fork, _)| *fork)
+	}
+
+	/// Whether transactions of `type_id` (`None` for legacy) are valid in the given block.
+	pub fn allows_tx_type(&self, type_id: Option<u8>, number: u64, timestamp: u64) -> bool {
+		self.fork_at(number, timestamp).allows_tx_type(type_id)
+	}
+}
+
+#[cfg(feature = "with-serde")]
+impl From<&crate::GenesisConfig> for ChainSpec {
+	/// Build the schedule of a genesis file. Paris is only scheduled when it is active at
+	/// genesis (a zero terminal total difficulty) or pinned by `mergeNetsplitBlock`.
+	fn from(config: &crate::GenesisConfig) -> Self {
+		let blocks = [
+			(Fork::Homestead, config.homestead_block),
+			(Fork::TangerineWhistle, config.eip150_block),
+			(Fork::SpuriousDragon, config.eip158_block),
+			(Fork::Byzantium, config.byzantium_block),
+			(Fork::Constantinople, config.constantinople_block),
+			(Fork::Petersburg, config.petersburg_block),
+			(Fork::Istanbul, config.istanbul_block),
+			(Fork::MuirGlacier, config.muir_glacier_block),
+			(Fork::Berlin, config.berlin_block),
+			(Fork::London, config.london_block),
+			(Fork::ArrowGlacier, config.arrow_glacier_block),
+			(Fork::GrayGlacier, config.gray_glacier_block),
+		];
+		let merged_at_genesis = config
+			.terminal_total_difficulty
+			.is_some_and(|ttd| ttd.is_zero());
+		let paris = if merged_at_genesis {
+			Some(0)
+		} else {
+			config.merge_netsplit_block
+		};
+		let times = [
+			(Fork::Shanghai, config.shanghai_time),
+			(Fork::Cancun, config.cancun_time),
+			(Fork::Prague, config.prague_time),
+		];
+
+		let mut spec = Self::new(config.chain_id);
+		for (fork, block) in blocks.iter().copied().chain([(Fork::Paris, paris)]) {
+			if let Some(block) = block {
+				spec = spec.with_fork(fork, ForkCondition::Block(block));
+			}
+		}
+		for (fork, time) in times {
+			if let Some(time) = time {
+				spec = spec.with_fork(fork, ForkCondition::Timestamp(time));
+			}
+		}
+		spec
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn mainnet_schedule() {
+		let spec = ChainSpec::mainnet();
+		assert_eq!(spec.fork_at(0, 0), Fork::Frontier);
+		assert_eq!(spec.fork_at(4_369_999, 0), Fork::SpuriousDragon);
+		assert_eq!(spec.fork_at(4_370_000, 0), Fork::Byzantium);
+		assert_eq!(spec.fork_at(7_280_000, 0), Fork::Petersburg);
+		assert_eq!(spec.fork_at(15_537_394, 1_663_224_179), Fork::Paris);
+		assert_eq!(spec.fork_at(17_034_870, 1_681_338_455), Fork::Shanghai);
+		assert_eq!(spec.fork_at(22_431_084, 1_746_612_311), Fork::Prague);
+
+		assert!(spec.allows_tx_type(None, 0, 0));
+		assert!(!spec.allows_tx_type(Some(1), 12_243_999, 0));
+		assert!(spec.allows_tx_type(Some(1), 12_244_000, 0));
+		assert!(!spec.allows_tx_type(Some(2), 12_244_000, 0));
+		assert!(!spec.allows_tx_type(Some(3), 19_000_000, 1_710_338_134));
+		assert!(spec.allows_tx_type(Some(3), 19_426_587, 1_710_338_135));
+		assert!(!spec.allows_tx_type(Some(5), u64::MAX, u64::MAX));
+	}
+
+	#[test]
+	fn testnet_schedules() {
+		let sepolia = ChainSpec::sepolia();
+		assert_eq!(sepolia.fork_at(0, 0), Fork::London);
+		assert!(!sepolia.is_active(Fork::ArrowGlacier, u64::MAX, u64::MAX));
+		assert_eq!(sepolia.fork_at(1_450_409, 0), Fork::Paris);
+
+		let holesky = ChainSpec::holesky();
+		assert_eq!(holesky.chain_id, 17_000);
+		assert_eq!(holesky.fork_at(0, 1_695_902_400), Fork::Paris);
+		assert_eq!(holesky.fork_at(1, 1_696_000_704), Fork::Shanghai);
+	}
+}
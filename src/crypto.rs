@@ -0,0 +1,229 @@
+//! Signer recovery for signed transactions, backed by the pure-Rust `k256` crate.
+
+use crate::{
+	Authorization, AuthorizationMessage, EIP1559Transaction, EIP1559TransactionMessage,
+	EIP2930Transaction, EIP2930TransactionMessage, EIP4844Transaction, EIP4844TransactionMessage,
+	EIP7702Transaction, EIP7702TransactionMessage, LegacyTransaction, LegacyTransactionMessage,
+	TransactionV1, TransactionV2, TransactionV3, TransactionV4,
+};
+use ethereum_types::{Address, H256};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+/// Error returned when the signer of a transaction cannot be recovered.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecoveryError {
+	/// The `r` or `s` value is not a valid secp256k1 scalar.
+	InvalidSignature,
+	/// The recovery id is not `0` or `1`.
+	InvalidRecoveryId,
+	/// No public key could be recovered from the signature and message hash.
+	RecoveryFailed,
+}
+
+/// Recover the address that signed `hash`, given a signature in Ethereum's `(y_parity, r, s)`
+/// form. High-s signatures (valid before Homestead) are normalized before recovery.
+pub(crate) fn recover_signer(
+	hash: H256,
+	y_parity: u8,
+	r: &H256,
+	s: &H256,
+) -> Result<Address, RecoveryError> {
+	if y_parity > 1 {
+		return Err(RecoveryError::InvalidRecoveryId);
+	}
+
+	let mut signature =
+		Signature::from_scalars(r.0, s.0).map_err(|_| RecoveryError::InvalidSignature)?;
+	let mut is_y_odd = y_parity == 1;
+	if let Some(normalized) = signature.normalize_s() {
+		signature = normalized;
+		is_y_odd = !is_y_odd;
+	}
+
+	let public = VerifyingKey::recover_from_prehash(
+		hash.as_bytes(),
+		&signature,
+		RecoveryId::new(is_y_odd, false),
+	)
+	.map_err(|_| RecoveryError::RecoveryFailed)?;
+
+	Ok(public_to_address(&public))
+}
+
+/// Derive the Ethereum address of an uncompressed secp256k1 public key.
+pub(crate) fn public_to_address(public: &VerifyingKey) -> Address {
+	let encoded = public.to_encoded_point(false);
+	let hash = Keccak256::digest(&encoded.as_bytes()[1..]);
+	Address::from_slice(&hash[12..])
+}
+
+/// Transaction whose sender can be recovered from its signature.
+pub trait RecoverableTransaction {
+	/// Recover the sender of the transaction.
+	fn recover_signer(&self) -> Result<Address, RecoveryError>;
+}
+
+impl LegacyTransaction {
+	/// Recover the sender of the transaction, honouring EIP-155 replay protection.
+	pub fn recover_signer(&self) -> Result<Address, RecoveryError> {
+		let hash = LegacyTransactionMessage::from(self.clone()).hash();
+		recover_signer(
+			hash,
+			self.signature.standard_v(),
+			self.signature.r(),
+			self.signature.s(),
+		)
+	}
+}
+
+macro_rules! impl_recover_signer {
+	($($tx:ty => $message:ty),*) => {
+		$(
+			impl $tx {
+				/// Recover the sender of the transaction.
+				pub fn recover_signer(&self) -> Result<Address, RecoveryError> {
+					let hash = <$message>::from(self.clone()).hash();
+					recover_signer(hash, self.odd_y_parity as u8, &self.r, &self.s)
+				}
+			}
+		)*
+	};
+}
+
+impl_recover_signer!(
+	EIP2930Transaction => EIP2930TransactionMessage,
+	EIP1559Transaction => EIP1559TransactionMessage,
+	EIP4844Transaction => EIP4844TransactionMessage,
+	EIP7702Transaction => EIP7702TransactionMessage
+);
+
+impl Authorization {
+	/// Recover the authority that signed this authorization tuple.
+	pub fn recover_authority(&self) -> Result<Address, RecoveryError> {
+		let hash = AuthorizationMessage::from(self.clone()).hash();
+		recover_signer(hash, self.y_parity, &self.r, &self.s)
+	}
+}
+
+macro_rules! impl_enum_recover_signer {
+	($($enum:ident { $($variant:ident),* }),*) => {
+		$(
+			impl $enum {
+				/// Recover the sender of the transaction.
+				pub fn recover_signer(&self) -> Result<Address, RecoveryError> {
+					match self {
+						$($enum::$variant(t) => t.recover_signer(),)*
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_enum_recover_signer!(
+	TransactionV1 { Legacy, EIP2930 },
+	TransactionV2 {
+		Legacy,
+		EIP2930,
+		EIP1559
+	},
+	TransactionV3 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844
+	},
+	TransactionV4 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844,
+		EIP7702
+	}
+);
+
+macro_rules! impl_recoverable_transaction {
+	($($tx:ident),*) => {
+		$(
+			impl RecoverableTransaction for $tx {
+				fn recover_signer(&self) -> Result<Address, RecoveryError> {
+					$tx::recover_signer(self)
+				}
+			}
+		)*
+	};
+}
+
+impl_recoverable_transaction!(
+	LegacyTransaction,
+	TransactionV1,
+	TransactionV2,
+	TransactionV3,
+	TransactionV4
+);
+
+#[cfg(test)]
+mod tests {
+	use crate::transaction::tests::{EIP4844_TX, EIP7702_TX};
+	use crate::{EnvelopedDecodable, TransactionV2, TransactionV4};
+	use ethereum_types::{Address, H160};
+	use hex_literal::hex;
+
+	/// Address of the private key `0x4646..46` used by the EIP-155 example.
+	const SENDER: Address = H160(hex!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"));
+
+	#[test]
+	fn recovers_eip155_legacy_sender() {
+		// Example transaction from EIP-155, signed with private key 0x4646..46.
+		let bytes = hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+		let tx = <TransactionV2 as EnvelopedDecodable>::decode(&bytes).unwrap();
+
+		assert_eq!(tx.recover_signer().unwrap(), SENDER);
+	}
+
+	#[test]
+	fn recovers_pre_eip155_legacy_senders() {
+		// The same transfer without replay protection, signed by the same key with each
+		// recovery id. Signatures were made with OpenSSL over independently computed hashes.
+		let v27 = hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000801ba02b10e6d8e4a922f6dadd1bbf689625aabf2e6f55f58ba5d55d5388a3a4a0becba067ee96f6cf4c034e602456e89276fe3c8c0613a63323823e0e634c40231c2ec5");
+		let v28 = hex!("f86c0a8504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000801ca03cf847b6f58b764c14ed479fddd43109b8e8199d259e512ac688cc91212bf13ca02519ec1abc3f181146639b822c14eaf41ebb2e48890068af06f7fe59b5457355");
+
+		for (bytes, v) in [(&v27[..], 27), (&v28[..], 28)] {
+			let tx = <TransactionV2 as EnvelopedDecodable>::decode(bytes).unwrap();
+			match &tx {
+				TransactionV2::Legacy(t) => {
+					assert_eq!(t.signature.v(), v);
+					assert_eq!(t.signature.chain_id(), None);
+				}
+				_ => panic!("expected a legacy transaction"),
+			}
+			assert_eq!(tx.recover_signer().unwrap(), SENDER);
+		}
+	}
+
+	#[test]
+	fn recovers_typed_transaction_senders() {
+		let eip2930 = hex!("01f8cd010a8506fc23ac0082c350943535353535353535353535353535353535353535880de0b6b3a764000083c0ffeef85bf85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a0000000000000000000000000000000000000000000000000000000000000000701a0f185a92aadb5a1245af4321b9d9215396eba8a79e54ffb6de8d78982690f25f0a0528f41cce20f0ebeb98f320043b7f96f9020c2ab2406fbc6a5f056449ac0e1d2");
+		let eip1559 = hex!("02f8cf010b84773594008509502f900082c350943535353535353535353535353535353535353535880de0b6b3a764000080f85bf85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a0000000000000000000000000000000000000000000000000000000000000000701a089d3f0688471b245c23dd35ea87a509e2046565e58a93250bc00cdf793489a96a0483fe7aa96fc6677bdc91e495dddcef69e53d3450a2cfab212d3c05c957f2a1f");
+
+		for bytes in [&eip2930[..], &eip1559[..], EIP4844_TX, EIP7702_TX] {
+			let tx = <TransactionV4 as EnvelopedDecodable>::decode(bytes).unwrap();
+			assert_eq!(tx.recover_signer().unwrap(), SENDER);
+		}
+	}
+
+	#[test]
+	fn recovers_authorization_authority() {
+		let tx = <TransactionV4 as EnvelopedDecodable>::decode(EIP7702_TX).unwrap();
+		let authorization = match &tx {
+			TransactionV4::EIP7702(t) => t.authorization_list[0].clone(),
+			_ => panic!("expected an EIP-7702 transaction"),
+		};
+		assert_eq!(authorization.recover_authority().unwrap(), SENDER);
+
+		let mut other_nonce = authorization;
+		other_nonce.nonce += 1;
+		assert_ne!(other_nonce.recover_authority(), Ok(SENDER));
+	}
+}
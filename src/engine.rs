@@ -0,0 +1,437 @@
+//! Engine API execution payloads and their conversion to and from blocks.
+
+use crate::{
+	Block, DecodeError, EnvelopedDecodable, EnvelopedDecoderError, EnvelopedEncodable,
+	PartialHeader, Withdrawal,
+};
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "with-serde")]
+use ethereum_types::U64;
+use ethereum_types::{Address, Bloom, H256, H64, U256};
+
+/// Error returned when an execution payload does not describe a valid block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PayloadError {
+	/// A transaction failed to decode.
+	Decode(DecodeError),
+	/// The hash of the block rebuilt from the payload differs from `block_hash`.
+	BlockHashMismatch { expected: H256, actual: H256 },
+}
+
+impl From<DecodeError> for PayloadError {
+	fn from(e: DecodeError) -> Self {
+		Self::Decode(e)
+	}
+}
+
+/// Execution payload of the Paris Engine API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "camelCase")
+)]
+pub struct ExecutionPayloadV1 {
+	pub parent_hash: H256,
+	pub fee_recipient: Address,
+	pub state_root: H256,
+	pub receipts_root: H256,
+	pub logs_bloom: Bloom,
+	pub prev_randao: H256,
+	pub block_number: U256,
+	pub gas_limit: U256,
+	pub gas_used: U256,
+	#[cfg_attr(
+		feature = "with-serde",
+		serde(with = "crate::serde_helpers::quantity_u64")
+	)]
+	pub timestamp: u64,
+	#[cfg_attr(feature = "with-serde", serde(with = "crate::serde_helpers::bytes"))]
+	pub extra_data: Vec<u8>,
+	pub base_fee_per_gas: U256,
+	pub block_hash: H256,
+	/// Transactions in their `EnvelopedEncodable` encoding.
+	#[cfg_attr(
+		feature = "with-serde",
+		serde(with = "crate::serde_helpers::bytes_list")
+	)]
+	pub transactions: Vec<Vec<u8>>,
+}
+
+/// Execution payload of the Shanghai Engine API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "camelCase")
+)]
+pub struct ExecutionPayloadV2 {
+	#[cfg_attr(feature = "with-serde", serde(flatten))]
+	pub payload: ExecutionPayloadV1,
+	#[cfg_attr(feature = "with-serde", serde(with = "raw_withdrawals"))]
+	pub withdrawals: Vec<Withdrawal>,
+}
+
+/// Withdrawal as the Engine API and JSON-RPC write it, with hex quantities.
+#[cfg(feature = "with-serde")]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawWithdrawal {
+	index: U64,
+	validator_index: U64,
+	address: Address,
+	amount: U64,
+}
+
+#[cfg(feature = "with-serde")]
+impl From<Withdrawal> for RawWithdrawal {
+	fn from(withdrawal: Withdrawal) -> Self {
+		Self {
+			index: withdrawal.index.into(),
+			validator_index: withdrawal.validator_index.into(),
+			address: withdrawal.address,
+			amount: withdrawal.amount.into(),
+		}
+	}
+}
+
+#[cfg(feature = "with-serde")]
+impl From<RawWithdrawal> for Withdrawal {
+	fn from(raw: RawWithdrawal) -> Self {
+		Self {
+			index: raw.index.as_u64(),
+			validator_index: raw.validator_index.as_u64(),
+			address: raw.address,
+			amount: raw.amount.as_u64(),
+		}
+	}
+}
+
+/// Lists of withdrawals in their [`RawWithdrawal`] form.
+#[cfg(feature = "with-serde")]
+pub(crate) mod raw_withdrawals {
+	use super::*;
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(
+		value: &[Withdrawal],
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(value.iter().cloned().map(RawWithdrawal::from))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Vec<Withdrawal>, D::Error> {
+		Ok(Vec::<RawWithdrawal>::deserialize(deserializer)?
+			.into_iter()
+			.map(Into::into)
+			.collect())
+	}
+}
+
+/// Execution payload of the Cancun Engine API, also used unchanged from Prague on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "camelCase")
+)]
+pub struct ExecutionPayloadV3 {
+	#[cfg_attr(feature = "with-serde", serde(flatten))]
+	pub payload: ExecutionPayloadV2,
+	pub blob_gas_used: U256,
+	pub excess_blob_gas: U256,
+}
+
+impl<T: EnvelopedEncodable> From<&Block<T>> for ExecutionPayloadV1 {
+	fn from(block: &Block<T>) -> Self {
+		let header = &block.header;
+		Self {
+			parent_hash: header.parent_hash,
+			fee_recipient: header.beneficiary,
+			state_root: header.state_root,
+			receipts_root: header.receipts_root,
+			logs_bloom: header.logs_bloom,
+			prev_randao: header.mix_hash,
+			block_number: header.number,
+			gas_limit: header.gas_limit,
+			gas_used: header.gas_used,
+			timestamp: header.timestamp,
+			extra_data: header.extra_data.clone(),
+			base_fee_per_gas: header.base_fee.unwrap_or_default(),
+			block_hash: header.hash(),
+			transactions: block
+				.transactions
+				.iter()
+				.map(|tx| EnvelopedEncodable::encode(tx).to_vec())
+				.collect(),
+		}
+	}
+}
+
+impl<T: EnvelopedEncodable> From<&Block<T>> for ExecutionPayloadV2 {
+	fn from(block: &Block<T>) -> Self {
+		Self {
+			payload: block.into(),
+			withdrawals: block.withdrawals.clone().unwrap_or_default(),
+		}
+	}
+}
+
+impl<T: EnvelopedEncodable> From<&Block<T>> for ExecutionPayloadV3 {
+	fn from(block: &Block<T>) -> Self {
+		Self {
+			payload: block.into(),
+			blob_gas_used: block.header.blob_gas_used.unwrap_or_default(),
+			excess_blob_gas: block.header.excess_blob_gas.unwrap_or_default(),
+		}
+	}
+}
+
+/// Fields a block needs beyond those of an [`ExecutionPayloadV1`].
+#[derive(Default)]
+struct PayloadExtension {
+	withdrawals: Option<Vec<Withdrawal>>,
+	blob_gas_used: Option<U256>,
+	excess_blob_gas: Option<U256>,
+	parent_beacon_block_root: Option<H256>,
+	requests_hash: Option<H256>,
+}
+
+impl ExecutionPayloadV1 {
+	/// Rebuild the block, checking its hash against `block_hash`.
+	pub fn try_into_block<T>(self) -> Result<Block<T>, PayloadError>
+	where
+		T: EnvelopedEncodable + EnvelopedDecodable,
+		DecodeError: From<EnvelopedDecoderError<T::PayloadDecoderError>>,
+	{
+		self.into_block_with(PayloadExtension::default())
+	}
+
+	fn into_block_with<T>(self, extension: PayloadExtension) -> Result<Block<T>, PayloadError>
+	where
+		T: EnvelopedEncodable + EnvelopedDecodable,
+		DecodeError: From<EnvelopedDecoderError<T::PayloadDecoderError>>,
+	{
+		let transactions = self
+			.transactions
+			.iter()
+			.enumerate()
+			.map(|(index, raw_tx)| {
+				T::decode(raw_tx).map_err(|e| DecodeError::Transaction {
+					index,
+					error: Box::new(e.into()),
+				})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let partial_header = PartialHeader {
+			parent_hash: self.parent_hash,
+			beneficiary: self.fee_recipient,
+			state_root: self.state_root,
+			receipts_root: self.receipts_root,
+			logs_bloom: self.logs_bloom,
+			difficulty: U256::zero(),
+			number: self.block_number,
+			gas_limit: self.gas_limit,
+			gas_used: self.gas_used,
+			timestamp: self.timestamp,
+			extra_data: self.extra_data,
+			mix_hash: self.prev_randao,
+			nonce: H64::zero(),
+			base_fee: Some(self.base_fee_per_gas),
+			blob_gas_used: extension.blob_gas_used,
+			excess_blob_gas: extension.excess_blob_gas,
+			parent_beacon_block_root: extension.parent_beacon_block_root,
+			requests_hash: extension.requests_hash,
+		};
+		let block = Block::new(
+			partial_header,
+			transactions,
+			Vec::new(),
+			extension.withdrawals,
+		);
+
+		let actual = block.header.hash();
+		if actual != self.block_hash {
+			return Err(PayloadError::BlockHashMismatch {
+				expected: self.block_hash,
+				actual,
+			});
+		}
+		Ok(block)
+	}
+}
+
+impl ExecutionPayloadV2 {
+	/// Rebuild the block, checking its hash against `block_hash`.
+	pub fn try_into_block<T>(self) -> Result<Block<T>, PayloadError>
+	where
+		T: EnvelopedEncodable + EnvelopedDecodable,
+		DecodeError: From<EnvelopedDecoderError<T::PayloadDecoderError>>,
+	{
+		self.payload.into_block_with(PayloadExtension {
+			withdrawals: Some(self.withdrawals),
+			..Default::default()
+		})
+	}
+}
+
+impl ExecutionPayloadV3 {
+	/// Rebuild the block, checking its hash against `block_hash`.
+	///
+	/// The parent beacon block root and, from Prague, the requests hash are not part of the
+	/// payload; the consensus client passes them alongside it.
+	pub fn try_into_block<T>(
+		self,
+		parent_beacon_block_root: H256,
+		requests_hash: Option<H256>,
+	) -> Result<Block<T>, PayloadError>
+	where
+		T: EnvelopedEncodable + EnvelopedDecodable,
+		DecodeError: From<EnvelopedDecoderError<T::PayloadDecoderError>>,
+	{
+		self.payload.payload.into_block_with(PayloadExtension {
+			withdrawals: Some(self.payload.withdrawals),
+			blob_gas_used: Some(self.blob_gas_used),
+			excess_blob_gas: Some(self.excess_blob_gas),
+			parent_beacon_block_root: Some(parent_beacon_block_root),
+			requests_hash,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		EIP1559Transaction, TransactionAction, TransactionV2, TransactionV4, INITIAL_BASE_FEE,
+	};
+	use alloc::vec;
+
+	fn block(timestamp: u64) -> Block<TransactionV4> {
+		let tx = TransactionV4::EIP1559(EIP1559Transaction {
+			chain_id: 1,
+			nonce: 7.into(),
+			max_priority_fee_per_gas: 1.into(),
+			max_fee_per_gas: 2_000_000_000u64.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Call(Address::repeat_byte(0x35)),
+			value: 1.into(),
+			input: vec![],
+			access_list: vec![],
+			odd_y_parity: true,
+			r: H256::repeat_byte(0x11),
+			s: H256::repeat_byte(0x22),
+		});
+		Block::new(
+			PartialHeader {
+				parent_hash: H256::repeat_byte(1),
+				beneficiary: Address::repeat_byte(2),
+				state_root: H256::repeat_byte(3),
+				receipts_root: H256::repeat_byte(4),
+				logs_bloom: Bloom::zero(),
+				difficulty: U256::zero(),
+				number: 100.into(),
+				gas_limit: 30_000_000.into(),
+				gas_used: 21_000.into(),
+				timestamp,
+				extra_data: b"engine".to_vec(),
+				mix_hash: H256::repeat_byte(5),
+				nonce: H64::zero(),
+				base_fee: Some(INITIAL_BASE_FEE.into()),
+				blob_gas_used: Some(0.into()),
+				excess_blob_gas: Some(131_072.into()),
+				parent_beacon_block_root: Some(H256::repeat_byte(6)),
+				requests_hash: None,
+			},
+			vec![tx],
+			vec![],
+			Some(vec![Withdrawal {
+				index: 1,
+				validator_index: 2,
+				address: Address::repeat_byte(7),
+				amount: 32_000_000_000,
+			}]),
+		)
+	}
+
+	#[test]
+	fn round_trips_v3_payload() {
+		let block = block(1_710_338_135);
+		let payload = ExecutionPayloadV3::from(&block);
+		assert_eq!(payload.payload.payload.block_hash, block.header.hash());
+
+		assert_eq!(
+			payload
+				.clone()
+				.try_into_block::<TransactionV4>(H256::repeat_byte(6), None),
+			Ok(block.clone())
+		);
+		assert!(matches!(
+			payload.try_into_block::<TransactionV4>(H256::zero(), None),
+			Err(PayloadError::BlockHashMismatch { .. })
+		));
+	}
+
+	#[test]
+	fn reports_undecodable_transactions() {
+		let mut payload = ExecutionPayloadV2::from(&block(0));
+		payload.payload.transactions.push(vec![0x7f]);
+
+		assert_eq!(
+			payload.try_into_block::<TransactionV2>(),
+			Err(PayloadError::Decode(DecodeError::Transaction {
+				index: 1,
+				error: Box::new(DecodeError::UnknownTypeId(0x7f)),
+			}))
+		);
+	}
+
+	#[cfg(feature = "with-serde")]
+	#[test]
+	fn reads_engine_api_json() {
+		let json = r#"{
+			"parentHash": "0x0101010101010101010101010101010101010101010101010101010101010101",
+			"feeRecipient": "0x0202020202020202020202020202020202020202",
+			"stateRoot": "0x0303030303030303030303030303030303030303030303030303030303030303",
+			"receiptsRoot": "0x0404040404040404040404040404040404040404040404040404040404040404",
+			"logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+			"prevRandao": "0x0505050505050505050505050505050505050505050505050505050505050505",
+			"blockNumber": "0x64",
+			"gasLimit": "0x1c9c380",
+			"gasUsed": "0x5208",
+			"timestamp": "0x65f1b057",
+			"extraData": "0x656e67696e65",
+			"baseFeePerGas": "0x3b9aca00",
+			"blockHash": "0x1f017aebc90291e25c0ee4493215772cc7a124e8829307a0b84886ba2b5f99f0",
+			"transactions": [
+				"0x02f86601070184773594008252089435353535353535353535353535353535353535350180c001a01111111111111111111111111111111111111111111111111111111111111111a02222222222222222222222222222222222222222222222222222222222222222"
+			],
+			"withdrawals": [
+				{
+					"index": "0x1",
+					"validatorIndex": "0x2",
+					"address": "0x0707070707070707070707070707070707070707",
+					"amount": "0x773594000"
+				}
+			],
+			"blobGasUsed": "0x0",
+			"excessBlobGas": "0x20000"
+		}"#;
+		let block = block(0x65f1b057);
+
+		let payload: ExecutionPayloadV3 = serde_json::from_str(json).unwrap();
+		assert_eq!(payload, ExecutionPayloadV3::from(&block));
+		assert_eq!(
+			serde_json::to_value(&payload).unwrap(),
+			serde_json::from_str::<serde_json::Value>(json).unwrap()
+		);
+
+		// Only the payload uses hex quantities; a block keeps its own serde format.
+		let block_json = serde_json::to_value(&block).unwrap();
+		assert_eq!(block_json["withdrawals"][0]["validatorIndex"], 2);
+		assert_eq!(block_json["withdrawals"][0]["amount"], 32_000_000_000_u64);
+	}
+}
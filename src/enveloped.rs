@@ -3,7 +3,9 @@ use bytes::BytesMut;
 /// DecoderError for typed transactions.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum EnvelopedDecoderError<T> {
-	UnknownTypeId,
+	/// The envelope starts with an unknown type byte.
+	UnknownTypeId(u8),
+	/// The payload failed to decode.
 	Payload(T),
 }
 
@@ -0,0 +1,70 @@
+use crate::EnvelopedDecoderError;
+use alloc::boxed::Box;
+use rlp::DecoderError;
+
+/// Structured error for decoding transactions, receipts and blocks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DecodeError {
+	/// The envelope starts with a type byte that is not known to the decoder.
+	UnknownTypeId(u8),
+	/// A named field failed to decode.
+	Field {
+		field: &'static str,
+		error: DecoderError,
+	},
+	/// The transaction at `index` within a block failed to decode.
+	Transaction {
+		index: usize,
+		error: Box<DecodeError>,
+	},
+	/// RLP error not tied to a specific field.
+	Rlp(DecoderError),
+}
+
+impl DecodeError {
+	/// Returns a closure that wraps an RLP error as a failure of `field`.
+	pub(crate) fn field(field: &'static str) -> impl FnOnce(DecoderError) -> Self {
+		move |error| Self::Field { field, error }
+	}
+}
+
+impl From<DecoderError> for DecodeError {
+	fn from(e: DecoderError) -> Self {
+		Self::Rlp(e)
+	}
+}
+
+impl From<DecodeError> for DecoderError {
+	fn from(e: DecodeError) -> Self {
+		match e {
+			DecodeError::UnknownTypeId(_) => DecoderError::Custom("unknown type id"),
+			DecodeError::Field { error, .. } => error,
+			DecodeError::Transaction { error, .. } => (*error).into(),
+			DecodeError::Rlp(error) => error,
+		}
+	}
+}
+
+impl From<DecoderError> for EnvelopedDecoderError<DecodeError> {
+	fn from(e: DecoderError) -> Self {
+		Self::Payload(e.into())
+	}
+}
+
+impl From<EnvelopedDecoderError<DecodeError>> for DecodeError {
+	fn from(e: EnvelopedDecoderError<DecodeError>) -> Self {
+		match e {
+			EnvelopedDecoderError::UnknownTypeId(type_id) => Self::UnknownTypeId(type_id),
+			EnvelopedDecoderError::Payload(e) => e,
+		}
+	}
+}
+
+impl From<EnvelopedDecoderError<DecoderError>> for DecodeError {
+	fn from(e: EnvelopedDecoderError<DecoderError>) -> Self {
+		match e {
+			EnvelopedDecoderError::UnknownTypeId(type_id) => Self::UnknownTypeId(type_id),
+			EnvelopedDecoderError::Payload(e) => e.into(),
+		}
+	}
+}
@@ -0,0 +1,369 @@
+//! Fee market arithmetic of EIP-1559 and EIP-4844.
+
+use crate::{
+	EIP1559Transaction, EIP2930Transaction, EIP4844Transaction, EIP7702Transaction, Header,
+	LegacyTransaction, TransactionV1, TransactionV2, TransactionV3, TransactionV4,
+};
+use ethereum_types::U256;
+
+/// Error returned by fee computations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeError {
+	/// [`BaseFeeParams::elasticity_multiplier`] is zero.
+	ZeroElasticityMultiplier,
+	/// [`BaseFeeParams::max_change_denominator`] is zero.
+	ZeroMaxChangeDenominator,
+	/// The denominator of [`fake_exponential`], such as a blob base fee update fraction, is zero.
+	ZeroDenominator,
+	/// The result does not fit in 256 bits.
+	Overflow,
+}
+
+/// Parameters of the EIP-1559 base fee update rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaseFeeParams {
+	/// Ratio of the gas limit to the gas target.
+	pub elasticity_multiplier: u64,
+	/// Inverse of the largest relative base fee change per block.
+	pub max_change_denominator: u64,
+}
+
+impl BaseFeeParams {
+	/// Parameters introduced in London.
+	pub const LONDON: Self = Self {
+		elasticity_multiplier: 2,
+		max_change_denominator: 8,
+	};
+}
+
+impl BaseFeeParams {
+	/// Check that neither parameter is zero.
+	pub fn validate(&self) -> Result<(), FeeError> {
+		if self.elasticity_multiplier == 0 {
+			return Err(FeeError::ZeroElasticityMultiplier);
+		}
+		if self.max_change_denominator == 0 {
+			return Err(FeeError::ZeroMaxChangeDenominator);
+		}
+		Ok(())
+	}
+}
+
+impl Default for BaseFeeParams {
+	fn default() -> Self {
+		Self::LONDON
+	}
+}
+
+/// Base fee of the child of a block with the given gas usage, gas limit and base fee. Fails
+/// only if `params` is invalid.
+pub fn calc_next_base_fee(
+	parent_gas_used: U256,
+	parent_gas_limit: U256,
+	parent_base_fee: U256,
+	params: BaseFeeParams,
+) -> Result<U256, FeeError> {
+	params.validate()?;
+	let gas_target = parent_gas_limit / params.elasticity_multiplier;
+	if gas_target.is_zero() || parent_gas_used == gas_target {
+		return Ok(parent_base_fee);
+	}
+
+	Ok(if parent_gas_used > gas_target {
+		let delta = parent_base_fee.saturating_mul(parent_gas_used - gas_target)
+			/ gas_target
+			/ params.max_change_denominator;
+		parent_base_fee.saturating_add(delta.max(U256::one()))
+	} else {
+		let delta = parent_base_fee.saturating_mul(gas_target - parent_gas_used)
+			/ gas_target
+			/ params.max_change_denominator;
+		parent_base_fee.saturating_sub(delta)
+	})
+}
+
+impl Header {
+	/// Base fee of this block's child, or `None` if this block predates London.
+	pub fn next_base_fee(&self, params: BaseFeeParams) -> Result<Option<U256>, FeeError> {
+		self.base_fee
+			.map(|base_fee| calc_next_base_fee(self.gas_used, self.gas_limit, base_fee, params))
+			.transpose()
+	}
+
+	/// Price of blob gas in this block, or `None` if this block predates Cancun.
+	pub fn blob_base_fee(&self, update_fraction: u64) -> Result<Option<U256>, FeeError> {
+		self.excess_blob_gas
+			.map(|excess| blob_base_fee(excess, update_fraction))
+			.transpose()
+	}
+}
+
+/// Lowest price of blob gas.
+pub const MIN_BLOB_BASE_FEE: u64 = 1;
+
+/// Blob base fee update fraction from Cancun.
+pub const BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN: u64 = 3_338_477;
+
+/// Blob base fee update fraction from Prague.
+pub const BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE: u64 = 5_007_716;
+
+/// Approximate `factor * e ** (numerator / denominator)` with the Taylor expansion of
+/// EIP-4844, failing if an intermediate value does not fit in 256 bits.
+pub fn fake_exponential(
+	factor: U256,
+	numerator: U256,
+	denominator: U256,
+) -> Result<U256, FeeError> {
+	if denominator.is_zero() {
+		return Err(FeeError::ZeroDenominator);
+	}
+
+	let mut i = U256::one();
+	let mut output = U256::zero();
+	let mut accum = factor.checked_mul(denominator).ok_or(FeeError::Overflow)?;
+	while !accum.is_zero() {
+		output = output.checked_add(accum).ok_or(FeeError::Overflow)?;
+		accum = accum.checked_mul(numerator).ok_or(FeeError::Overflow)?
+			/ denominator.checked_mul(i).ok_or(FeeError::Overflow)?;
+		i += U256::one();
+	}
+	Ok(output / denominator)
+}
+
+/// Price of blob gas given the block's `excess_blob_gas`.
+pub fn blob_base_fee(excess_blob_gas: U256, update_fraction: u64) -> Result<U256, FeeError> {
+	fake_exponential(
+		MIN_BLOB_BASE_FEE.into(),
+		excess_blob_gas,
+		update_fraction.into(),
+	)
+}
+
+fn dynamic_fee_price(max_fee: U256, max_priority_fee: U256, base_fee: U256) -> U256 {
+	max_fee.min(base_fee.saturating_add(max_priority_fee))
+}
+
+fn dynamic_fee_tip(max_fee: U256, max_priority_fee: U256, base_fee: U256) -> Option<U256> {
+	max_fee
+		.checked_sub(base_fee)
+		.map(|headroom| headroom.min(max_priority_fee))
+}
+
+impl LegacyTransaction {
+	/// Price paid per unit of gas.
+	pub fn effective_gas_price(&self, _base_fee: U256) -> U256 {
+		self.gas_price
+	}
+
+	/// Part of the gas price paid to the block producer, or `None` if the gas price is below
+	/// `base_fee`.
+	pub fn effective_tip(&self, base_fee: U256) -> Option<U256> {
+		self.gas_price.checked_sub(base_fee)
+	}
+}
+
+impl EIP2930Transaction {
+	/// Price paid per unit of gas.
+	pub fn effective_gas_price(&self, _base_fee: U256) -> U256 {
+		self.gas_price
+	}
+
+	/// Part of the gas price paid to the block producer, or `None` if the gas price is below
+	/// `base_fee`.
+	pub fn effective_tip(&self, base_fee: U256) -> Option<U256> {
+		self.gas_price.checked_sub(base_fee)
+	}
+}
+
+macro_rules! impl_dynamic_fee {
+	($($tx:ty),*) => {
+		$(
+			impl $tx {
+				/// Price paid per unit of gas in a block with `base_fee`.
+				pub fn effective_gas_price(&self, base_fee: U256) -> U256 {
+					dynamic_fee_price(self.max_fee_per_gas, self.max_priority_fee_per_gas, base_fee)
+				}
+
+				/// Part of the gas price paid to the block producer in a block with
+				/// `base_fee`, or `None` if `max_fee_per_gas` is below `base_fee`.
+				pub fn effective_tip(&self, base_fee: U256) -> Option<U256> {
+					dynamic_fee_tip(self.max_fee_per_gas, self.max_priority_fee_per_gas, base_fee)
+				}
+			}
+		)*
+	};
+}
+
+impl_dynamic_fee!(EIP1559Transaction, EIP4844Transaction, EIP7702Transaction);
+
+macro_rules! impl_enum_fee {
+	($($enum:ident { $($variant:ident),* }),*) => {
+		$(
+			impl $enum {
+				/// Price paid per unit of gas in a block with `base_fee`.
+				pub fn effective_gas_price(&self, base_fee: U256) -> U256 {
+					match self {
+						$($enum::$variant(t) => t.effective_gas_price(base_fee),)*
+					}
+				}
+
+				/// Part of the gas price paid to the block producer in a block with
+				/// `base_fee`, or `None` if the transaction cannot pay `base_fee`.
+				pub fn effective_tip(&self, base_fee: U256) -> Option<U256> {
+					match self {
+						$($enum::$variant(t) => t.effective_tip(base_fee),)*
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_enum_fee!(
+	TransactionV1 { Legacy, EIP2930 },
+	TransactionV2 {
+		Legacy,
+		EIP2930,
+		EIP1559
+	},
+	TransactionV3 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844
+	},
+	TransactionV4 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844,
+		EIP7702
+	}
+);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TransactionAction;
+	use alloc::vec;
+	use ethereum_types::{H160, H256};
+
+	#[test]
+	fn computes_next_base_fee() {
+		let params = BaseFeeParams::LONDON;
+		let limit = U256::from(30_000_000);
+		let base = U256::from(1_000_000_000);
+		assert_eq!(
+			calc_next_base_fee(15_000_000.into(), limit, base, params),
+			Ok(base)
+		);
+		assert_eq!(
+			calc_next_base_fee(30_000_000.into(), limit, base, params),
+			Ok(1_125_000_000.into())
+		);
+		assert_eq!(
+			calc_next_base_fee(0.into(), limit, base, params),
+			Ok(875_000_000.into())
+		);
+		// The base fee always increases by at least one wei above the target.
+		assert_eq!(
+			calc_next_base_fee(15_000_001.into(), limit, 7.into(), params),
+			Ok(8.into())
+		);
+
+		let custom = BaseFeeParams {
+			elasticity_multiplier: 4,
+			max_change_denominator: 50,
+		};
+		assert_eq!(
+			calc_next_base_fee(30_000_000.into(), limit, base, custom),
+			Ok(1_060_000_000.into())
+		);
+
+		for (elasticity_multiplier, max_change_denominator, error) in [
+			(0, 8, FeeError::ZeroElasticityMultiplier),
+			(2, 0, FeeError::ZeroMaxChangeDenominator),
+		] {
+			let params = BaseFeeParams {
+				elasticity_multiplier,
+				max_change_denominator,
+			};
+			assert_eq!(
+				calc_next_base_fee(30_000_000.into(), limit, base, params),
+				Err(error)
+			);
+		}
+	}
+
+	#[test]
+	fn computes_blob_base_fee() {
+		let fraction = BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN;
+		assert_eq!(blob_base_fee(0.into(), fraction), Ok(1.into()));
+		assert_eq!(blob_base_fee(2_314_057.into(), fraction), Ok(1.into()));
+		assert_eq!(blob_base_fee(2_314_058.into(), fraction), Ok(2.into()));
+		assert_eq!(blob_base_fee(10_000_000.into(), fraction), Ok(19.into()));
+		assert_eq!(blob_base_fee(1.into(), 0), Err(FeeError::ZeroDenominator));
+
+		// An untrusted header can carry any excess blob gas; the price must not loop forever.
+		assert_eq!(blob_base_fee(U256::MAX, fraction), Err(FeeError::Overflow));
+		assert_eq!(
+			blob_base_fee(u64::MAX.into(), fraction),
+			Err(FeeError::Overflow)
+		);
+
+		// Test vectors from the EIP-4844 reference implementation.
+		for (factor, numerator, denominator, expected) in [
+			(1u64, 0u64, 1u64, 1u64),
+			(38493, 0, 1000, 38493),
+			(0, 1234, 2345, 0),
+			(1, 2, 1, 6),
+			(1, 4, 2, 6),
+			(1, 3, 1, 16),
+			(1, 6, 2, 18),
+			(1, 4, 1, 49),
+			(1, 8, 2, 50),
+			(10, 8, 2, 542),
+			(11, 8, 2, 596),
+			(1, 5, 1, 136),
+			(1, 5, 2, 11),
+			(2, 5, 2, 23),
+		] {
+			assert_eq!(
+				fake_exponential(factor.into(), numerator.into(), denominator.into()),
+				Ok(expected.into())
+			);
+		}
+	}
+
+	#[test]
+	fn computes_effective_gas_price_and_tip() {
+		let tx = EIP1559Transaction {
+			chain_id: 1,
+			nonce: 0.into(),
+			max_priority_fee_per_gas: 2.into(),
+			max_fee_per_gas: 10.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Call(H160::zero()),
+			value: 0.into(),
+			input: vec![],
+			access_list: vec![],
+			odd_y_parity: false,
+			r: H256::zero(),
+			s: H256::zero(),
+		};
+		assert_eq!(tx.effective_gas_price(5.into()), 7.into());
+		assert_eq!(tx.effective_tip(5.into()), Some(2.into()));
+		assert_eq!(tx.effective_gas_price(9.into()), 10.into());
+		assert_eq!(tx.effective_tip(9.into()), Some(1.into()));
+		assert_eq!(tx.effective_tip(11.into()), None);
+
+		let tx = TransactionV4::EIP1559(tx);
+		assert_eq!(tx.effective_gas_price(5.into()), 7.into());
+		assert_eq!(tx.effective_tip(5.into()), Some(2.into()));
+	}
+}
@@ -0,0 +1,46 @@
+/// Protocol upgrades, in activation order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fork {
+	Frontier,
+	Homestead,
+	TangerineWhistle,
+	SpuriousDragon,
+	Byzantium,
+	Constantinople,
+	Petersburg,
+	Istanbul,
+	MuirGlacier,
+	Berlin,
+	London,
+	ArrowGlacier,
+	GrayGlacier,
+	Paris,
+	Shanghai,
+	Cancun,
+	Prague,
+}
+
+impl Fork {
+	/// Whether legacy receipts carry an EIP-658 status code instead of a state root.
+	pub fn has_receipt_status(self) -> bool {
+		self >= Fork::Byzantium
+	}
+
+	/// Whether the EIP-2718 transaction (and receipt) type `type_id` is valid in this fork.
+	/// `None` stands for legacy transactions, which are valid in every fork.
+	pub fn allows_tx_type(self, type_id: Option<u8>) -> bool {
+		match type_id {
+			None => true,
+			Some(1) => self >= Fork::Berlin,
+			Some(2) => self >= Fork::London,
+			Some(3) => self >= Fork::Cancun,
+			Some(4) => self >= Fork::Prague,
+			Some(_) => false,
+		}
+	}
+}
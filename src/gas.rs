@@ -0,0 +1,273 @@
+//! Intrinsic gas of transactions: the gas charged before any EVM execution.
+
+use crate::{
+	AccessListItem, EIP1559Transaction, EIP2930Transaction, EIP4844Transaction, EIP7702Transaction,
+	Fork, LegacyTransaction, TransactionAction, TransactionV1, TransactionV2, TransactionV3,
+	TransactionV4,
+};
+
+/// Base cost of every transaction.
+pub const TX_GAS: u64 = 21_000;
+/// Additional cost of a contract creation from Homestead.
+pub const TX_CREATE_GAS: u64 = 32_000;
+/// Cost of a zero calldata byte.
+pub const TX_DATA_ZERO_GAS: u64 = 4;
+/// Cost of a non-zero calldata byte before Istanbul.
+pub const TX_DATA_NON_ZERO_GAS_FRONTIER: u64 = 68;
+/// Cost of a non-zero calldata byte from Istanbul (EIP-2028).
+pub const TX_DATA_NON_ZERO_GAS_ISTANBUL: u64 = 16;
+/// Cost of an address in an access list (EIP-2930).
+pub const TX_ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+/// Cost of a storage key in an access list (EIP-2930).
+pub const TX_ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1_900;
+/// Cost of each 32-byte word of contract creation initcode (EIP-3860).
+pub const INITCODE_WORD_GAS: u64 = 2;
+/// Cost of an authorization tuple (EIP-7702).
+pub const PER_EMPTY_ACCOUNT_COST: u64 = 25_000;
+/// Cost of a calldata token under the EIP-7623 floor.
+pub const TOTAL_COST_FLOOR_PER_TOKEN: u64 = 10;
+
+/// Gas a transaction is charged before execution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IntrinsicGas {
+	/// Gas charged up front for the transaction's base cost, calldata, access list, initcode
+	/// and authorizations.
+	pub standard: u64,
+	/// Lowest total gas the transaction can be charged under EIP-7623, or zero before Prague.
+	pub floor: u64,
+}
+
+impl IntrinsicGas {
+	/// Lowest gas limit with which the transaction can be included.
+	pub fn min_gas_limit(&self) -> u64 {
+		self.standard.max(self.floor)
+	}
+}
+
+fn intrinsic_gas(
+	fork: Fork,
+	create: bool,
+	input: &[u8],
+	access_list: &[AccessListItem],
+	authorizations: usize,
+) -> IntrinsicGas {
+	let zero_bytes = input.iter().filter(|b| **b == 0).count() as u64;
+	let non_zero_bytes = input.len() as u64 - zero_bytes;
+	let non_zero_gas = if fork >= Fork::Istanbul {
+		TX_DATA_NON_ZERO_GAS_ISTANBUL
+	} else {
+		TX_DATA_NON_ZERO_GAS_FRONTIER
+	};
+
+	let mut standard = TX_GAS
+		.saturating_add(zero_bytes * TX_DATA_ZERO_GAS)
+		.saturating_add(non_zero_bytes.saturating_mul(non_zero_gas));
+
+	if create && fork >= Fork::Homestead {
+		standard = standard.saturating_add(TX_CREATE_GAS);
+	}
+	if create && fork >= Fork::Shanghai {
+		let words = (input.len() as u64).div_ceil(32);
+		standard = standard.saturating_add(words * INITCODE_WORD_GAS);
+	}
+
+	for item in access_list {
+		standard = standard
+			.saturating_add(TX_ACCESS_LIST_ADDRESS_GAS)
+			.saturating_add(
+				(item.storage_keys.len() as u64).saturating_mul(TX_ACCESS_LIST_STORAGE_KEY_GAS),
+			);
+	}
+	standard =
+		standard.saturating_add((authorizations as u64).saturating_mul(PER_EMPTY_ACCOUNT_COST));
+
+	let floor = if fork >= Fork::Prague {
+		let tokens = zero_bytes.saturating_add(non_zero_bytes.saturating_mul(4));
+		TX_GAS.saturating_add(tokens.saturating_mul(TOTAL_COST_FLOOR_PER_TOKEN))
+	} else {
+		0
+	};
+
+	IntrinsicGas { standard, floor }
+}
+
+impl LegacyTransaction {
+	/// Intrinsic gas of the transaction under the rules of `fork`.
+	pub fn intrinsic_gas(&self, fork: Fork) -> IntrinsicGas {
+		intrinsic_gas(
+			fork,
+			self.action == TransactionAction::Create,
+			&self.input,
+			&[],
+			0,
+		)
+	}
+}
+
+impl EIP2930Transaction {
+	/// Intrinsic gas of the transaction under the rules of `fork`.
+	pub fn intrinsic_gas(&self, fork: Fork) -> IntrinsicGas {
+		intrinsic_gas(
+			fork,
+			self.action == TransactionAction::Create,
+			&self.input,
+			&self.access_list,
+			0,
+		)
+	}
+}
+
+impl EIP1559Transaction {
+	/// Intrinsic gas of the transaction under the rules of `fork`.
+	pub fn intrinsic_gas(&self, fork: Fork) -> IntrinsicGas {
+		intrinsic_gas(
+			fork,
+			self.action == TransactionAction::Create,
+			&self.input,
+			&self.access_list,
+			0,
+		)
+	}
+}
+
+impl EIP4844Transaction {
+	/// Intrinsic gas of the transaction under the rules of `fork`. Blob gas is accounted
+	/// separately.
+	pub fn intrinsic_gas(&self, fork: Fork) -> IntrinsicGas {
+		intrinsic_gas(fork, false, &self.input, &self.access_list, 0)
+	}
+}
+
+impl EIP7702Transaction {
+	/// Intrinsic gas of the transaction under the rules of `fork`.
+	pub fn intrinsic_gas(&self, fork: Fork) -> IntrinsicGas {
+		intrinsic_gas(
+			fork,
+			false,
+			&self.input,
+			&self.access_list,
+			self.authorization_list.len(),
+		)
+	}
+}
+
+macro_rules! impl_enum_intrinsic_gas {
+	($($enum:ident { $($variant:ident),* }),*) => {
+		$(
+			impl $enum {
+				/// Intrinsic gas of the transaction under the rules of `fork`.
+				pub fn intrinsic_gas(&self, fork: Fork) -> IntrinsicGas {
+					match self {
+						$($enum::$variant(t) => t.intrinsic_gas(fork),)*
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_enum_intrinsic_gas!(
+	TransactionV1 { Legacy, EIP2930 },
+	TransactionV2 {
+		Legacy,
+		EIP2930,
+		EIP1559
+	},
+	TransactionV3 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844
+	},
+	TransactionV4 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844,
+		EIP7702
+	}
+);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::TransactionSignature;
+	use alloc::{vec, vec::Vec};
+	use ethereum_types::{H160, H256};
+
+	fn legacy(action: TransactionAction, input: Vec<u8>) -> LegacyTransaction {
+		LegacyTransaction {
+			nonce: 0.into(),
+			gas_price: 1.into(),
+			gas_limit: 100_000.into(),
+			action,
+			value: 0.into(),
+			input,
+			signature: TransactionSignature::new(27, H256::repeat_byte(1), H256::repeat_byte(1))
+				.unwrap(),
+		}
+	}
+
+	#[test]
+	fn prices_calldata_and_creation_by_fork() {
+		let call = legacy(TransactionAction::Call(H160::zero()), vec![]);
+		assert_eq!(call.intrinsic_gas(Fork::Frontier).standard, 21_000);
+
+		let call = legacy(TransactionAction::Call(H160::zero()), vec![0, 0, 1, 2]);
+		assert_eq!(
+			call.intrinsic_gas(Fork::Byzantium).standard,
+			21_000 + 8 + 136
+		);
+		assert_eq!(call.intrinsic_gas(Fork::Istanbul).standard, 21_000 + 8 + 32);
+
+		let create = legacy(TransactionAction::Create, vec![1; 33]);
+		assert_eq!(
+			create.intrinsic_gas(Fork::Frontier).standard,
+			21_000 + 33 * 68
+		);
+		assert_eq!(
+			create.intrinsic_gas(Fork::Berlin).standard,
+			53_000 + 33 * 16
+		);
+		assert_eq!(
+			create.intrinsic_gas(Fork::Shanghai).standard,
+			53_000 + 33 * 16 + 2 * 2
+		);
+	}
+
+	#[test]
+	fn prices_access_lists_and_calldata_floor() {
+		let tx = EIP1559Transaction {
+			chain_id: 1,
+			nonce: 0.into(),
+			max_priority_fee_per_gas: 0.into(),
+			max_fee_per_gas: 0.into(),
+			gas_limit: 0.into(),
+			action: TransactionAction::Call(H160::zero()),
+			value: 0.into(),
+			input: vec![1; 1000],
+			access_list: vec![
+				AccessListItem {
+					address: H160::zero(),
+					storage_keys: vec![H256::zero(), H256::repeat_byte(1)],
+				},
+				AccessListItem {
+					address: H160::repeat_byte(1),
+					storage_keys: vec![],
+				},
+			],
+			odd_y_parity: false,
+			r: H256::zero(),
+			s: H256::zero(),
+		};
+
+		let cancun = tx.intrinsic_gas(Fork::Cancun);
+		assert_eq!(cancun.standard, 21_000 + 16_000 + 2 * 2_400 + 2 * 1_900);
+		assert_eq!(cancun.floor, 0);
+
+		let prague = TransactionV4::EIP1559(tx).intrinsic_gas(Fork::Prague);
+		assert_eq!(prague.standard, cancun.standard);
+		assert_eq!(prague.floor, 21_000 + 4_000 * 10);
+		assert_eq!(prague.min_gas_limit(), 61_000);
+	}
+}
@@ -0,0 +1,458 @@
+//! Geth-style `genesis.json` specifications and construction of block 0.
+
+use crate::{
+	serde_helpers::{bytes, option_quantity, quantity, quantity_u64},
+	util::{sec_trie_root, KeccakHasher},
+	Account, Block, Bytes, EnvelopedEncodable, Header, PartialHeader, Withdrawal, INITIAL_BASE_FEE,
+};
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+use ethereum_types::{Address, Bloom, H256, H64, U256};
+use hash_db::Hasher;
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// Gas limit used when the genesis file leaves it at zero.
+pub const DEFAULT_GENESIS_GAS_LIMIT: u64 = 4_712_388;
+
+/// Difficulty used when the genesis file sets neither a difficulty nor a mix hash.
+pub const DEFAULT_GENESIS_DIFFICULTY: u64 = 131_072;
+
+/// `requests_hash` of a block without execution-layer requests (EIP-7685).
+pub const EMPTY_REQUESTS_HASH: H256 = H256([
+	0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f, 0xb9, 0x24,
+	0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52, 0xb8, 0x55,
+]);
+
+/// Chain configuration of a genesis file: chain id and fork activation points.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisConfig {
+	pub chain_id: u64,
+	pub homestead_block: Option<u64>,
+	#[serde(rename = "eip150Block")]
+	pub eip150_block: Option<u64>,
+	#[serde(rename = "eip155Block")]
+	pub eip155_block: Option<u64>,
+	#[serde(rename = "eip158Block")]
+	pub eip158_block: Option<u64>,
+	pub byzantium_block: Option<u64>,
+	pub constantinople_block: Option<u64>,
+	pub petersburg_block: Option<u64>,
+	pub istanbul_block: Option<u64>,
+	pub muir_glacier_block: Option<u64>,
+	pub berlin_block: Option<u64>,
+	pub london_block: Option<u64>,
+	pub arrow_glacier_block: Option<u64>,
+	pub gray_glacier_block: Option<u64>,
+	pub merge_netsplit_block: Option<u64>,
+	#[serde(default, deserialize_with = "option_quantity::deserialize")]
+	pub terminal_total_difficulty: Option<U256>,
+	pub shanghai_time: Option<u64>,
+	pub cancun_time: Option<u64>,
+	pub prague_time: Option<u64>,
+}
+
+/// Account pre-allocated in the genesis state.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct GenesisAccount {
+	#[serde(deserialize_with = "quantity::deserialize")]
+	pub balance: U256,
+	#[serde(default, deserialize_with = "quantity_u64::deserialize")]
+	pub nonce: u64,
+	#[serde(default, deserialize_with = "bytes::deserialize")]
+	pub code: Bytes,
+	#[serde(default, deserialize_with = "deserialize_storage")]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+impl GenesisAccount {
+	/// Root of the account's storage trie. Zero-valued slots are not stored.
+	pub fn storage_root(&self) -> H256 {
+		sec_trie_root(
+			self.storage
+				.iter()
+				.filter(|(_, value)| !value.is_zero())
+				.map(|(key, value)| (*key, rlp::encode(&U256::from_big_endian(value.as_bytes())))),
+		)
+	}
+
+	/// State trie representation of the account.
+	pub fn account(&self) -> Account {
+		Account {
+			nonce: self.nonce.into(),
+			balance: self.balance,
+			storage_root: self.storage_root(),
+			code_hash: KeccakHasher::hash(&self.code),
+		}
+	}
+}
+
+/// Geth-style `genesis.json` specification.
+///
+/// Quantities may be JSON numbers, hex strings or decimal strings. Numbers wider than 64 bits,
+/// like the `terminalTotalDifficulty` of geth's mainnet genesis, need the
+/// `arbitrary-precision` feature to be read exactly and are rejected without it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Genesis {
+	#[serde(default)]
+	pub config: GenesisConfig,
+	#[serde(default, deserialize_with = "quantity_u64::deserialize")]
+	pub nonce: u64,
+	#[serde(default, deserialize_with = "quantity_u64::deserialize")]
+	pub timestamp: u64,
+	#[serde(default, deserialize_with = "bytes::deserialize")]
+	pub extra_data: Bytes,
+	#[serde(default, deserialize_with = "quantity::deserialize")]
+	pub gas_limit: U256,
+	/// Difficulty of block 0. Only when it is absent does the mix hash decide whether
+	/// [`DEFAULT_GENESIS_DIFFICULTY`] applies.
+	#[serde(default, deserialize_with = "option_quantity::deserialize")]
+	pub difficulty: Option<U256>,
+	#[serde(default)]
+	pub mix_hash: H256,
+	#[serde(default)]
+	pub coinbase: Address,
+	#[serde(default, deserialize_with = "deserialize_alloc")]
+	pub alloc: BTreeMap<Address, GenesisAccount>,
+	#[serde(default, deserialize_with = "quantity_u64::deserialize")]
+	pub number: u64,
+	#[serde(default, deserialize_with = "quantity::deserialize")]
+	pub gas_used: U256,
+	#[serde(default)]
+	pub parent_hash: H256,
+	#[serde(default, deserialize_with = "option_quantity::deserialize")]
+	pub base_fee_per_gas: Option<U256>,
+	#[serde(default, deserialize_with = "option_quantity::deserialize")]
+	pub excess_blob_gas: Option<U256>,
+	#[serde(default, deserialize_with = "option_quantity::deserialize")]
+	pub blob_gas_used: Option<U256>,
+}
+
+impl Genesis {
+	/// State root of the allocated accounts.
+	pub fn state_root(&self) -> H256 {
+		sec_trie_root(
+			self.alloc
+				.iter()
+				.map(|(address, account)| (*address, rlp::encode(&account.account()))),
+		)
+	}
+
+	/// Genesis block. Header fields introduced by forks active at genesis are filled in with
+	/// the same defaults as geth.
+	pub fn block<T: EnvelopedEncodable>(&self) -> Block<T> {
+		let config = &self.config;
+		let timestamp_fork = |time: Option<u64>| time.is_some_and(|t| t <= self.timestamp);
+		let london = config.london_block == Some(0);
+		let shanghai = timestamp_fork(config.shanghai_time);
+		let cancun = timestamp_fork(config.cancun_time);
+		let prague = timestamp_fork(config.prague_time);
+
+		let difficulty = match self.difficulty {
+			Some(difficulty) => difficulty,
+			None if self.mix_hash.is_zero() => DEFAULT_GENESIS_DIFFICULTY.into(),
+			None => U256::zero(),
+		};
+		let gas_limit = if self.gas_limit.is_zero() {
+			DEFAULT_GENESIS_GAS_LIMIT.into()
+		} else {
+			self.gas_limit
+		};
+
+		let partial_header = PartialHeader {
+			parent_hash: self.parent_hash,
+			beneficiary: self.coinbase,
+			state_root: self.state_root(),
+			receipts_root: KeccakHasher::hash(&rlp::NULL_RLP),
+			logs_bloom: Bloom::zero(),
+			difficulty,
+			number: self.number.into(),
+			gas_limit,
+			gas_used: self.gas_used,
+			timestamp: self.timestamp,
+			extra_data: self.extra_data.clone(),
+			mix_hash: self.mix_hash,
+			nonce: H64::from_low_u64_be(self.nonce),
+			base_fee: london.then(|| self.base_fee_per_gas.unwrap_or(INITIAL_BASE_FEE.into())),
+			blob_gas_used: cancun.then(|| self.blob_gas_used.unwrap_or_default()),
+			excess_blob_gas: cancun.then(|| self.excess_blob_gas.unwrap_or_default()),
+			parent_beacon_block_root: cancun.then(H256::zero),
+			requests_hash: prague.then_some(EMPTY_REQUESTS_HASH),
+		};
+		let withdrawals: Option<Vec<Withdrawal>> = shanghai.then(Vec::new);
+
+		Block::new(partial_header, vec![], vec![], withdrawals)
+	}
+
+	/// Genesis header.
+	pub fn header(&self) -> Header {
+		self.block::<crate::TransactionV4>().header
+	}
+}
+
+fn deserialize_alloc<'de, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<BTreeMap<Address, GenesisAccount>, D::Error> {
+	BTreeMap::<String, GenesisAccount>::deserialize(deserializer)?
+		.into_iter()
+		.map(|(address, account)| {
+			let raw = bytes::decode_hex::<D::Error>(&address)?;
+			if raw.len() != 20 {
+				return Err(D::Error::custom("invalid address length in alloc"));
+			}
+			Ok((Address::from_slice(&raw), account))
+		})
+		.collect()
+}
+
+fn deserialize_storage<'de, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<BTreeMap<H256, H256>, D::Error> {
+	let word = |s: &str| -> Result<H256, D::Error> {
+		let raw = bytes::decode_hex::<D::Error>(s)?;
+		if raw.len() > 32 {
+			return Err(D::Error::custom("storage word longer than 32 bytes"));
+		}
+		let mut word = H256::zero();
+		word[32 - raw.len()..].copy_from_slice(&raw);
+		Ok(word)
+	};
+
+	BTreeMap::<String, String>::deserialize(deserializer)?
+		.iter()
+		.map(|(key, value)| Ok((word(key)?, word(value)?)))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::MemoryTrie;
+	use hex_literal::hex;
+
+	const GENESIS: &str = r#"{
+		"config": {
+			"chainId": 1337,
+			"homesteadBlock": 0,
+			"eip150Block": 0,
+			"eip155Block": 0,
+			"eip158Block": 0,
+			"byzantiumBlock": 0,
+			"constantinopleBlock": 0,
+			"petersburgBlock": 0,
+			"istanbulBlock": 0,
+			"berlinBlock": 0,
+			"londonBlock": 0,
+			"terminalTotalDifficulty": 0,
+			"shanghaiTime": 0,
+			"cancunTime": 0
+		},
+		"nonce": "0x0",
+		"timestamp": "0x65156994",
+		"extraData": "0x",
+		"gasLimit": "0x1c9c380",
+		"difficulty": "0x0",
+		"mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+		"coinbase": "0x0000000000000000000000000000000000000000",
+		"alloc": {
+			"71562b71999873db5b286df957af199ec94617f7": {
+				"balance": "1000000000000000000000"
+			},
+			"0x4242424242424242424242424242424242424242": {
+				"balance": "0x0",
+				"nonce": "0x1",
+				"code": "0x600160005500",
+				"storage": {
+					"0x01": "0x2a",
+					"0x0000000000000000000000000000000000000000000000000000000000000002": "0x00"
+				}
+			}
+		},
+		"baseFeePerGas": null
+	}"#;
+
+	#[test]
+	fn builds_genesis_block() {
+		let genesis: Genesis = serde_json::from_str(GENESIS).unwrap();
+		assert_eq!(genesis.config.chain_id, 1337);
+		assert_eq!(genesis.config.terminal_total_difficulty, Some(U256::zero()));
+		assert_eq!(
+			crate::ChainSpec::from(&genesis.config).fork_at(0, genesis.timestamp),
+			crate::Fork::Cancun
+		);
+
+		let funded = genesis
+			.alloc
+			.get(&Address::from(hex!(
+				"71562b71999873db5b286df957af199ec94617f7"
+			)))
+			.unwrap();
+		assert_eq!(funded.balance, U256::exp10(21));
+		assert_eq!(
+			funded.account().storage_root,
+			KeccakHasher::hash(&rlp::NULL_RLP)
+		);
+
+		let contract = genesis.alloc.get(&Address::repeat_byte(0x42)).unwrap();
+		let mut storage = MemoryTrie::new();
+		storage.insert(
+			KeccakHasher::hash(H256::from_low_u64_be(1).as_bytes()).as_bytes(),
+			rlp::encode(&0x2au8).to_vec(),
+		);
+		assert_eq!(contract.storage_root(), storage.root());
+
+		let mut state = MemoryTrie::new();
+		for (address, account) in &genesis.alloc {
+			state.insert(
+				KeccakHasher::hash(address.as_bytes()).as_bytes(),
+				rlp::encode(&account.account()).to_vec(),
+			);
+		}
+		assert_eq!(genesis.state_root(), state.root());
+
+		let block = genesis.block::<crate::TransactionV4>();
+		let header = &block.header;
+		assert_eq!(header.state_root, state.root());
+		assert_eq!(header.difficulty, U256::zero());
+		assert_eq!(header.gas_limit, 30_000_000.into());
+		assert_eq!(header.base_fee, Some(INITIAL_BASE_FEE.into()));
+		assert_eq!(
+			header.withdrawals_root,
+			Some(KeccakHasher::hash(&rlp::NULL_RLP))
+		);
+		assert_eq!(header.blob_gas_used, Some(U256::zero()));
+		assert_eq!(header.parent_beacon_block_root, Some(H256::zero()));
+		assert_eq!(header.requests_hash, None);
+		assert_eq!(block.withdrawals, Some(vec![]));
+		assert_eq!(block.verify_body(), Ok(()));
+		assert_eq!(genesis.header().hash(), header.hash());
+	}
+
+	/// Mainnet `genesis.json` as shipped by geth, with all but one of its 8893 allocations left
+	/// out.
+	#[cfg(feature = "arbitrary-precision")]
+	const MAINNET_GENESIS: &str = r#"{
+		"config": {
+			"chainId": 1,
+			"homesteadBlock": 1150000,
+			"daoForkBlock": 1920000,
+			"daoForkSupport": true,
+			"eip150Block": 2463000,
+			"eip155Block": 2675000,
+			"eip158Block": 2675000,
+			"byzantiumBlock": 4370000,
+			"constantinopleBlock": 7280000,
+			"petersburgBlock": 7280000,
+			"istanbulBlock": 9069000,
+			"muirGlacierBlock": 9200000,
+			"berlinBlock": 12244000,
+			"londonBlock": 12965000,
+			"arrowGlacierBlock": 13773000,
+			"grayGlacierBlock": 15050000,
+			"terminalTotalDifficulty": 58750000000000000000000,
+			"shanghaiTime": 1681338455,
+			"cancunTime": 1710338135,
+			"ethash": {}
+		},
+		"nonce": "0x42",
+		"timestamp": "0x0",
+		"extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+		"gasLimit": "0x1388",
+		"difficulty": "0x400000000",
+		"mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+		"coinbase": "0x0000000000000000000000000000000000000000",
+		"alloc": {
+			"000d836201318ec6899a67540690382780743280": {
+				"balance": "0xad78ebc5ac6200000"
+			}
+		},
+		"number": "0x0",
+		"gasUsed": "0x0",
+		"parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+		"baseFeePerGas": null
+	}"#;
+
+	/// geth's mainnet genesis writes `terminalTotalDifficulty` as a JSON number above 64 bits.
+	#[cfg(feature = "arbitrary-precision")]
+	#[test]
+	fn builds_mainnet_genesis_header() {
+		let genesis: Genesis = serde_json::from_str(MAINNET_GENESIS).unwrap();
+		assert_eq!(genesis.difficulty, Some(0x4_0000_0000_u64.into()));
+		assert_eq!(
+			genesis.config.terminal_total_difficulty,
+			Some(U256::from_dec_str("58750000000000000000000").unwrap())
+		);
+
+		// The full allocation is too large to embed; substitute its well-known state root.
+		let mut header = genesis.header();
+		assert_eq!(header.difficulty, 0x4_0000_0000_u64.into());
+		assert_eq!(header.base_fee, None);
+		header.state_root =
+			hex!("d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544").into();
+		assert_eq!(
+			header.compute_hash(),
+			H256::from(hex!(
+				"d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+			))
+		);
+	}
+
+	#[test]
+	fn builds_geth_custom_genesis() {
+		// `customg` from geth's `core/genesis_test.go`: defaults everywhere except one account
+		// with a balance and a storage slot, whose hash geth pins as `customghash`.
+		let genesis: Genesis = serde_json::from_str(
+			r#"{
+				"config": { "chainId": 1, "homesteadBlock": 3 },
+				"alloc": {
+					"0x0100000000000000000000000000000000000000": {
+						"balance": "0x1",
+						"storage": {
+							"0x0100000000000000000000000000000000000000000000000000000000000000":
+								"0x0100000000000000000000000000000000000000000000000000000000000000"
+						}
+					}
+				}
+			}"#,
+		)
+		.unwrap();
+
+		let block = genesis.block::<crate::TransactionV4>();
+		assert_eq!(block.header.gas_limit, DEFAULT_GENESIS_GAS_LIMIT.into());
+		assert_eq!(block.header.difficulty, DEFAULT_GENESIS_DIFFICULTY.into());
+		assert_eq!(
+			block.header.hash(),
+			H256::from(hex!(
+				"89c99d90b79719238d2645c7642f2c9295246e80775b38cfd162b696817fbd50"
+			))
+		);
+	}
+
+	#[test]
+	fn sets_base_fee_only_when_london_is_active_at_block_0() {
+		let mut genesis = Genesis::default();
+		genesis.config.london_block = Some(0);
+		assert_eq!(genesis.header().base_fee, Some(INITIAL_BASE_FEE.into()));
+
+		// Like geth, a genesis numbered past a later London block still gets no base fee.
+		genesis.config.london_block = Some(5);
+		genesis.number = 10;
+		assert_eq!(genesis.header().base_fee, None);
+	}
+
+	#[test]
+	fn defaults_difficulty_only_when_absent() {
+		let mut genesis = Genesis::default();
+		assert_eq!(
+			genesis.header().difficulty,
+			DEFAULT_GENESIS_DIFFICULTY.into()
+		);
+
+		genesis.mix_hash = H256::repeat_byte(1);
+		assert_eq!(genesis.header().difficulty, U256::zero());
+
+		let genesis: Genesis = serde_json::from_str(r#"{"difficulty": "0x0"}"#).unwrap();
+		assert_eq!(genesis.difficulty, Some(U256::zero()));
+		assert_eq!(genesis.header().difficulty, U256::zero());
+	}
+}
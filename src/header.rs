@@ -1,28 +1,30 @@
+use core::ops::Deref;
 use ethereum_types::{Bloom, H160, H256, H64, U256};
-use lru::LruCache;
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 use sha3::{Digest, Keccak256};
-use parking_lot::Mutex;
-use std::sync::OnceLock;
 
-use crate::Bytes;
+use crate::{Bytes, DecodeError};
 
-fn header_hash_cache() -> &'static Mutex<lru::LruCache<Vec<u8>, H256>> {
-	pub static CACHE: OnceLock<Mutex<lru::LruCache<Vec<u8>, H256>>> = OnceLock::new();
-	CACHE.get_or_init(|| {
-		let cache_size = std::num::NonZeroUsize::new(100).unwrap();
-		Mutex::new(LruCache::new(cache_size))
-	})
-}
+/// Number of fields in a header before the optional fields introduced by later forks.
+const BASE_FIELD_COUNT: usize = 15;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-#[derive(rlp::RlpEncodable, rlp::RlpDecodable)]
+/// Number of optional trailing fields: `base_fee`, `withdrawals_root`, `blob_gas_used`,
+/// `excess_blob_gas`, `parent_beacon_block_root` and `requests_hash`.
+const OPTIONAL_FIELD_COUNT: usize = 6;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(
 	feature = "with-codec",
 	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
 )]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
 /// Ethereum header definition.
-
+///
+/// The trailing optional fields were appended to the header by successive forks: `base_fee`
+/// (London), `withdrawals_root` (Shanghai), `blob_gas_used`, `excess_blob_gas` and
+/// `parent_beacon_block_root` (Cancun), and `requests_hash` (Prague). They are encoded in that
+/// order up to the last one that is set; an unset field before it is encoded as its zero value,
+/// so such a header decodes back with that field set.
 pub struct Header {
 	pub parent_hash: H256,
 	pub ommers_hash: H256,
@@ -39,10 +41,125 @@ pub struct Header {
 	pub extra_data: Bytes,
 	pub mix_hash: H256,
 	pub nonce: H64,
-	pub base_fee: U256,
+	pub base_fee: Option<U256>,
+	pub withdrawals_root: Option<H256>,
+	pub blob_gas_used: Option<U256>,
+	pub excess_blob_gas: Option<U256>,
+	pub parent_beacon_block_root: Option<H256>,
+	pub requests_hash: Option<H256>,
+}
+
+impl Encodable for Header {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		let optional_fields = self
+			.optional_fields()
+			.iter()
+			.rposition(|(_, present)| *present)
+			.map_or(0, |last| last + 1);
+
+		s.begin_list(BASE_FIELD_COUNT + optional_fields);
+		s.append(&self.parent_hash);
+		s.append(&self.ommers_hash);
+		s.append(&self.beneficiary);
+		s.append(&self.state_root);
+		s.append(&self.transactions_root);
+		s.append(&self.receipts_root);
+		s.append(&self.logs_bloom);
+		s.append(&self.difficulty);
+		s.append(&self.number);
+		s.append(&self.gas_limit);
+		s.append(&self.gas_used);
+		s.append(&self.timestamp);
+		s.append(&self.extra_data);
+		s.append(&self.mix_hash);
+		s.append(&self.nonce);
+
+		if optional_fields > 0 {
+			s.append(&self.base_fee.unwrap_or_default());
+		}
+		if optional_fields > 1 {
+			s.append(&self.withdrawals_root.unwrap_or_default());
+		}
+		if optional_fields > 2 {
+			s.append(&self.blob_gas_used.unwrap_or_default());
+		}
+		if optional_fields > 3 {
+			s.append(&self.excess_blob_gas.unwrap_or_default());
+		}
+		if optional_fields > 4 {
+			s.append(&self.parent_beacon_block_root.unwrap_or_default());
+		}
+		if optional_fields > 5 {
+			s.append(&self.requests_hash.unwrap_or_default());
+		}
+	}
+}
+
+impl Header {
+	/// Decode the RLP header, reporting which field failed to decode.
+	pub fn decode_rlp(rlp: &Rlp) -> Result<Self, DecodeError> {
+		let item_count = rlp.item_count()?;
+		if !(BASE_FIELD_COUNT..=BASE_FIELD_COUNT + OPTIONAL_FIELD_COUNT).contains(&item_count) {
+			return Err(DecoderError::RlpIncorrectListLen.into());
+		}
+
+		Ok(Self {
+			parent_hash: rlp.val_at(0).map_err(DecodeError::field("parent_hash"))?,
+			ommers_hash: rlp.val_at(1).map_err(DecodeError::field("ommers_hash"))?,
+			beneficiary: rlp.val_at(2).map_err(DecodeError::field("beneficiary"))?,
+			state_root: rlp.val_at(3).map_err(DecodeError::field("state_root"))?,
+			transactions_root: rlp
+				.val_at(4)
+				.map_err(DecodeError::field("transactions_root"))?,
+			receipts_root: rlp.val_at(5).map_err(DecodeError::field("receipts_root"))?,
+			logs_bloom: rlp.val_at(6).map_err(DecodeError::field("logs_bloom"))?,
+			difficulty: rlp.val_at(7).map_err(DecodeError::field("difficulty"))?,
+			number: rlp.val_at(8).map_err(DecodeError::field("number"))?,
+			gas_limit: rlp.val_at(9).map_err(DecodeError::field("gas_limit"))?,
+			gas_used: rlp.val_at(10).map_err(DecodeError::field("gas_used"))?,
+			timestamp: rlp.val_at(11).map_err(DecodeError::field("timestamp"))?,
+			extra_data: rlp.val_at(12).map_err(DecodeError::field("extra_data"))?,
+			mix_hash: rlp.val_at(13).map_err(DecodeError::field("mix_hash"))?,
+			nonce: rlp.val_at(14).map_err(DecodeError::field("nonce"))?,
+			base_fee: optional_field(rlp, item_count, 15, "base_fee")?,
+			withdrawals_root: optional_field(rlp, item_count, 16, "withdrawals_root")?,
+			blob_gas_used: optional_field(rlp, item_count, 17, "blob_gas_used")?,
+			excess_blob_gas: optional_field(rlp, item_count, 18, "excess_blob_gas")?,
+			parent_beacon_block_root: optional_field(
+				rlp,
+				item_count,
+				19,
+				"parent_beacon_block_root",
+			)?,
+			requests_hash: optional_field(rlp, item_count, 20, "requests_hash")?,
+		})
+	}
+}
+
+fn optional_field<T: Decodable>(
+	rlp: &Rlp,
+	item_count: usize,
+	index: usize,
+	field: &'static str,
+) -> Result<Option<T>, DecodeError> {
+	if index < item_count {
+		rlp.val_at(index)
+			.map(Some)
+			.map_err(DecodeError::field(field))
+	} else {
+		Ok(None)
+	}
+}
+
+impl Decodable for Header {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Self::decode_rlp(rlp).map_err(Into::into)
+	}
 }
 
 impl Header {
+	/// Build a header from its partial form. `withdrawals_root` is left unset; `Block::new`
+	/// fills it in from the block body.
 	#[must_use]
 	pub fn new(partial_header: PartialHeader, ommers_hash: H256, transactions_root: H256) -> Self {
 		Self {
@@ -62,23 +179,122 @@ impl Header {
 			mix_hash: partial_header.mix_hash,
 			nonce: partial_header.nonce,
 			base_fee: partial_header.base_fee,
+			withdrawals_root: None,
+			blob_gas_used: partial_header.blob_gas_used,
+			excess_blob_gas: partial_header.excess_blob_gas,
+			parent_beacon_block_root: partial_header.parent_beacon_block_root,
+			requests_hash: partial_header.requests_hash,
 		}
 	}
 
+	/// Name of the first optional field that is set while an earlier one is not. Optional
+	/// fields are introduced by successive forks and encoded positionally, so such a header
+	/// does not survive an RLP round trip.
+	#[must_use]
+	pub fn optional_field_gap(&self) -> Option<&'static str> {
+		let fields = self.optional_fields();
+		let first_unset = fields.iter().position(|(_, present)| !present)?;
+		fields[first_unset..]
+			.iter()
+			.find(|(_, present)| *present)
+			.map(|(field, _)| *field)
+	}
+
+	fn optional_fields(&self) -> [(&'static str, bool); OPTIONAL_FIELD_COUNT] {
+		[
+			("base_fee", self.base_fee.is_some()),
+			("withdrawals_root", self.withdrawals_root.is_some()),
+			("blob_gas_used", self.blob_gas_used.is_some()),
+			("excess_blob_gas", self.excess_blob_gas.is_some()),
+			(
+				"parent_beacon_block_root",
+				self.parent_beacon_block_root.is_some(),
+			),
+			("requests_hash", self.requests_hash.is_some()),
+		]
+	}
+
+	/// Hash of the header. With the `header-cache` feature the result is memoized in
+	/// [`HeaderHashCache::global`](crate::HeaderHashCache::global).
+	#[must_use]
+	pub fn hash(&self) -> H256 {
+		#[cfg(feature = "header-cache")]
+		{
+			crate::HeaderHashCache::global().hash(self)
+		}
+		#[cfg(not(feature = "header-cache"))]
+		{
+			self.compute_hash()
+		}
+	}
+
+	/// Hash of the header, always computed from its RLP encoding.
+	#[must_use]
+	pub fn compute_hash(&self) -> H256 {
+		H256::from_slice(Keccak256::digest(rlp::encode(self)).as_slice())
+	}
+
+	/// Compute the hash once and carry it along with the header.
+	#[must_use]
+	pub fn seal(self) -> SealedHeader {
+		let hash = self.compute_hash();
+		SealedHeader { header: self, hash }
+	}
+}
+
+/// Header together with its precomputed hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SealedHeader {
+	header: Header,
+	hash: H256,
+}
+
+impl SealedHeader {
+	/// Pair a header with a hash that is already known to match it, e.g. one read from a
+	/// trusted database. The hash is not checked.
+	#[must_use]
+	pub fn new_unchecked(header: Header, hash: H256) -> Self {
+		Self { header, hash }
+	}
+
 	#[must_use]
 	pub fn hash(&self) -> H256 {
-		let rlp_encoded = &rlp::encode(self);
-		header_hash_cache()
-			.lock()
-			.get_or_insert(rlp_encoded.to_vec(), move || {
-				H256::from_slice(Keccak256::digest(rlp_encoded).as_slice())
-			})
-			.clone()
+		self.hash
+	}
+
+	#[must_use]
+	pub fn header(&self) -> &Header {
+		&self.header
+	}
+
+	#[must_use]
+	pub fn into_header(self) -> Header {
+		self.header
+	}
+}
+
+impl Deref for SealedHeader {
+	type Target = Header;
+
+	fn deref(&self) -> &Header {
+		&self.header
+	}
+}
+
+impl From<Header> for SealedHeader {
+	fn from(header: Header) -> Self {
+		header.seal()
+	}
+}
+
+impl From<SealedHeader> for Header {
+	fn from(sealed: SealedHeader) -> Self {
+		sealed.header
 	}
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-/// Partial header definition without ommers hash and transactions root.
+/// Partial header definition without ommers hash, transactions root and withdrawals root.
 pub struct PartialHeader {
 	pub parent_hash: H256,
 	pub beneficiary: H160,
@@ -93,7 +309,11 @@ pub struct PartialHeader {
 	pub extra_data: Bytes,
 	pub mix_hash: H256,
 	pub nonce: H64,
-	pub base_fee: U256,
+	pub base_fee: Option<U256>,
+	pub blob_gas_used: Option<U256>,
+	pub excess_blob_gas: Option<U256>,
+	pub parent_beacon_block_root: Option<H256>,
+	pub requests_hash: Option<H256>,
 }
 
 impl From<Header> for PartialHeader {
@@ -113,6 +333,167 @@ impl From<Header> for PartialHeader {
 			mix_hash: header.mix_hash,
 			nonce: header.nonce,
 			base_fee: header.base_fee,
+			blob_gas_used: header.blob_gas_used,
+			excess_blob_gas: header.excess_blob_gas,
+			parent_beacon_block_root: header.parent_beacon_block_root,
+			requests_hash: header.requests_hash,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	fn mainnet_genesis() -> Header {
+		Header {
+			parent_hash: H256::zero(),
+			ommers_hash: hex!("1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347")
+				.into(),
+			beneficiary: H160::zero(),
+			state_root: hex!("d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544")
+				.into(),
+			transactions_root: hex!(
+				"56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+			)
+			.into(),
+			receipts_root: hex!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+				.into(),
+			logs_bloom: Bloom::zero(),
+			difficulty: 0x4_0000_0000_u64.into(),
+			number: 0.into(),
+			gas_limit: 5000.into(),
+			gas_used: 0.into(),
+			timestamp: 0,
+			extra_data: hex!("11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa")
+				.to_vec(),
+			mix_hash: H256::zero(),
+			nonce: hex!("0000000000000042").into(),
+			base_fee: None,
+			withdrawals_root: None,
+			blob_gas_used: None,
+			excess_blob_gas: None,
+			parent_beacon_block_root: None,
+			requests_hash: None,
 		}
 	}
+
+	#[test]
+	fn hashes_pre_london_header_without_base_fee() {
+		let header = mainnet_genesis();
+
+		assert_eq!(
+			header.compute_hash(),
+			H256::from(hex!(
+				"d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+			))
+		);
+		assert_eq!(Rlp::new(&rlp::encode(&header)).item_count(), Ok(15));
+		assert_eq!(rlp::decode::<Header>(&rlp::encode(&header)), Ok(header));
+	}
+
+	/// Decode a header whose 256-byte logs bloom is empty from the RLP around the bloom, and
+	/// check that it hashes to `hash` and re-encodes to the same bytes.
+	fn decode_header_vector(before_bloom: &[u8], after_bloom: &[u8], hash: H256) -> Header {
+		let encoded = [before_bloom, &[0; 256], after_bloom].concat();
+		let header = rlp::decode::<Header>(&encoded).unwrap();
+		assert_eq!(header.compute_hash(), hash);
+		assert_eq!(header.hash(), hash);
+		assert_eq!(rlp::encode(&header).to_vec(), encoded);
+		header
+	}
+
+	#[test]
+	fn hashes_london_header_with_base_fee() {
+		// Sepolia and Holesky genesis headers, both with London active from genesis.
+		let sepolia = decode_header_vector(
+			&hex!("f9021da00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347940000000000000000000000000000000000000000a05eb6e371a698b8d68f665192350ffcecbbbf322916f4b51bd79bb6887da3f494a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b90100"),
+			&hex!("83020000808401c9c38080846159af19a05365706f6c69612c20417468656e732c204174746963612c2047726565636521a00000000000000000000000000000000000000000000000000000000000000000880000000000000000843b9aca00"),
+			hex!("25a5cc106eea7138acab33231d7160d69cb777ee0c2c553fcddf5138993e6dd9").into(),
+		);
+		assert_eq!(
+			sepolia.extra_data,
+			b"Sepolia, Athens, Attica, Greece!".to_vec()
+		);
+		assert_eq!(sepolia.base_fee, Some(1_000_000_000.into()));
+		assert_eq!(sepolia.withdrawals_root, None);
+
+		let holesky = decode_header_vector(
+			&hex!("f901faa00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347940000000000000000000000000000000000000000a069d8c9d72f6fa4ad42d4702b433707212f90db395eb54dc20bc85de253788783a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b90100"),
+			&hex!("018084017d784080846515699480a00000000000000000000000000000000000000000000000000000000000000000880000000000001234843b9aca00"),
+			hex!("b5f7f912443c940f21fd611f12828d75b534364ed9e95ca4e307729a4661bde4").into(),
+		);
+		assert_eq!(holesky.timestamp, 1_695_902_100);
+		assert_eq!(holesky.base_fee, Some(1_000_000_000.into()));
+	}
+
+	#[test]
+	fn hashes_cancun_header_with_blob_gas_and_beacon_root() {
+		// Hoodi genesis header, with Cancun active from genesis.
+		let hoodi = decode_header_vector(
+			&hex!("f9023ea00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347940000000000000000000000000000000000000000a0da87d7f5f91c51508791bbcbd4aa5baf04917830b86985eeb9ad3d5bfb657576a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b90100"),
+			&hex!("01808402255100808467d80ec080a00000000000000000000000000000000000000000000000000000000000000000880000000000001234843b9aca00a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b4218080a00000000000000000000000000000000000000000000000000000000000000000"),
+			hex!("bbe312868b376a3001692a646dd2d7d1e4406380dfd86b98aa8a34d1557c971b").into(),
+		);
+		assert_eq!(
+			hoodi.withdrawals_root,
+			Some(hex!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421").into())
+		);
+		assert_eq!(hoodi.blob_gas_used, Some(0.into()));
+		assert_eq!(hoodi.excess_blob_gas, Some(0.into()));
+		assert_eq!(hoodi.parent_beacon_block_root, Some(H256::zero()));
+		assert_eq!(hoodi.requests_hash, None);
+	}
+
+	#[test]
+	fn round_trips_optional_fields_by_count() {
+		let mut header = mainnet_genesis();
+		header.base_fee = Some(7.into());
+		header.withdrawals_root = Some(H256::repeat_byte(1));
+		header.blob_gas_used = Some(131_072.into());
+		header.excess_blob_gas = Some(0.into());
+		header.parent_beacon_block_root = Some(H256::repeat_byte(2));
+		header.requests_hash = Some(H256::repeat_byte(3));
+
+		let encoded = rlp::encode(&header);
+		assert_eq!(Rlp::new(&encoded).item_count(), Ok(21));
+		assert_eq!(rlp::decode::<Header>(&encoded), Ok(header.clone()));
+
+		header.blob_gas_used = None;
+		header.excess_blob_gas = None;
+		header.parent_beacon_block_root = None;
+		header.requests_hash = None;
+		let encoded = rlp::encode(&header);
+		assert_eq!(Rlp::new(&encoded).item_count(), Ok(17));
+		assert_eq!(rlp::decode::<Header>(&encoded), Ok(header));
+
+		let mut stream = RlpStream::new_list(14);
+		for _ in 0..14 {
+			stream.append(&0_u8);
+		}
+		assert_eq!(
+			rlp::decode::<Header>(&stream.out()),
+			Err(DecoderError::RlpIncorrectListLen)
+		);
+	}
+
+	#[test]
+	fn encodes_gap_in_optional_fields_as_zero() {
+		let mut header = mainnet_genesis();
+		header.base_fee = Some(7.into());
+		header.blob_gas_used = Some(131_072.into());
+		header.excess_blob_gas = Some(0.into());
+		assert_eq!(header.optional_field_gap(), Some("blob_gas_used"));
+
+		let mut filled = header.clone();
+		filled.withdrawals_root = Some(H256::zero());
+		assert_eq!(filled.optional_field_gap(), None);
+
+		let encoded = rlp::encode(&header);
+		assert_eq!(Rlp::new(&encoded).item_count(), Ok(19));
+		assert_eq!(encoded, rlp::encode(&filled));
+		assert_eq!(header.compute_hash(), filled.compute_hash());
+		assert_eq!(rlp::decode::<Header>(&encoded), Ok(filled));
+	}
 }
@@ -4,20 +4,71 @@ extern crate alloc;
 
 mod account;
 mod block;
+mod chain_spec;
+#[cfg(feature = "secp256k1")]
+mod crypto;
+mod engine;
 mod enveloped;
+mod error;
+mod fee;
+mod fork;
+mod gas;
+#[cfg(feature = "with-serde")]
+mod genesis;
 mod header;
+#[cfg(feature = "header-cache")]
+mod header_cache;
 mod log;
+mod meta;
 mod receipt;
+#[cfg(feature = "with-serde")]
+mod rpc;
+#[cfg(feature = "with-serde")]
+mod serde_helpers;
+#[cfg(feature = "secp256k1")]
+mod signer;
 mod transaction;
 pub mod util;
+mod validation;
+mod withdrawal;
 
 // Alias for `Vec<u8>`. This type alias is necessary for rlp-derive to work correctly.
 type Bytes = alloc::vec::Vec<u8>;
 
-pub use account::Account;
+pub use account::{
+	verify_account_proof, Account, AccountProofError, StorageProof, VerifiedAccount,
+};
 pub use block::*;
+pub use chain_spec::{ChainSpec, ForkCondition};
+#[cfg(feature = "secp256k1")]
+pub use crypto::{RecoverableTransaction, RecoveryError};
+pub use engine::{ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadError};
 pub use enveloped::*;
-pub use header::{Header, PartialHeader};
-pub use log::Log;
+pub use error::DecodeError;
+pub use fee::{
+	blob_base_fee, calc_next_base_fee, fake_exponential, BaseFeeParams, FeeError,
+	BLOB_BASE_FEE_UPDATE_FRACTION_CANCUN, BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, MIN_BLOB_BASE_FEE,
+};
+pub use fork::Fork;
+pub use gas::*;
+#[cfg(feature = "with-serde")]
+pub use genesis::{
+	Genesis, GenesisAccount, GenesisConfig, DEFAULT_GENESIS_DIFFICULTY, DEFAULT_GENESIS_GAS_LIMIT,
+	EMPTY_REQUESTS_HASH,
+};
+pub use header::{Header, PartialHeader, SealedHeader};
+#[cfg(feature = "header-cache")]
+pub use header_cache::{HeaderHashCache, HeaderHashCacheStats, DEFAULT_HEADER_HASH_CACHE_CAPACITY};
+pub use log::{logs_bloom, Log, LogFilter};
+pub use meta::{MetaError, ReceiptWithMeta, TransactionWithMeta};
 pub use receipt::*;
+#[cfg(feature = "with-serde")]
+pub use rpc::{RpcBlock, RpcBlockTransactions, RpcLog, RpcReceipt, RpcTransaction};
+#[cfg(feature = "secp256k1")]
+pub use signer::{LocalSigner, SignableMessage, Signer, SignerError};
 pub use transaction::*;
+pub use validation::{
+	HeaderViolation, TransactionViolation, GAS_LIMIT_BOUND_DIVISOR, INITIAL_BASE_FEE,
+	MAX_EXTRA_DATA_SIZE, MAX_INITCODE_SIZE, MIN_GAS_LIMIT,
+};
+pub use withdrawal::Withdrawal;
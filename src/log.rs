@@ -1,6 +1,6 @@
-use crate::Bytes;
-use alloc::vec::Vec;
-use ethereum_types::{H160, H256};
+use crate::{Bytes, Header};
+use alloc::{collections::BTreeSet, vec::Vec};
+use ethereum_types::{Bloom, BloomInput, H160, H256, U256};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[derive(rlp::RlpEncodable, rlp::RlpDecodable)]
@@ -14,3 +14,246 @@ pub struct Log {
 	pub topics: Vec<H256>,
 	pub data: Bytes,
 }
+
+impl Log {
+	/// Add the address and topics of this log to `bloom`.
+	pub fn accrue_bloom(&self, bloom: &mut Bloom) {
+		bloom.accrue(BloomInput::Raw(&self.address[..]));
+		for topic in &self.topics {
+			bloom.accrue(BloomInput::Raw(&topic[..]));
+		}
+	}
+}
+
+/// Compute the bloom filter of a list of logs, as stored in a receipt's `logs_bloom`.
+pub fn logs_bloom<'a, I>(logs: I) -> Bloom
+where
+	I: IntoIterator<Item = &'a Log>,
+{
+	let mut bloom = Bloom::zero();
+	for log in logs {
+		log.accrue_bloom(&mut bloom);
+	}
+	bloom
+}
+
+/// Log selection criteria of `eth_getLogs`.
+///
+/// An empty address set matches any address. `topics[i]` lists the accepted values of a log's
+/// `i`-th topic, an empty list accepting any value; logs with fewer topics than `topics` never
+/// match.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogFilter {
+	/// First block to search, or `None` for no lower bound.
+	pub from_block: Option<U256>,
+	/// Last block to search, or `None` for no upper bound.
+	pub to_block: Option<U256>,
+	pub addresses: BTreeSet<H160>,
+	pub topics: Vec<Vec<H256>>,
+}
+
+impl LogFilter {
+	/// Create a filter that matches every log.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Only match blocks from `number` on.
+	pub fn from_block(mut self, number: impl Into<U256>) -> Self {
+		self.from_block = Some(number.into());
+		self
+	}
+
+	/// Only match blocks up to and including `number`.
+	pub fn to_block(mut self, number: impl Into<U256>) -> Self {
+		self.to_block = Some(number.into());
+		self
+	}
+
+	/// Also match logs emitted by `address`.
+	pub fn address(mut self, address: H160) -> Self {
+		self.addresses.insert(address);
+		self
+	}
+
+	/// Accept any of `alternatives` as the topic at `position`, or any topic if empty.
+	pub fn topic(mut self, position: usize, alternatives: Vec<H256>) -> Self {
+		if self.topics.len() <= position {
+			self.topics.resize(position + 1, Vec::new());
+		}
+		self.topics[position] = alternatives;
+		self
+	}
+
+	/// Whether `number` is within the filter's block range.
+	pub fn matches_block_number(&self, number: U256) -> bool {
+		self.from_block.iter().all(|&from| number >= from)
+			&& self.to_block.iter().all(|&to| number <= to)
+	}
+
+	/// Whether `log` matches the filter's addresses and topics.
+	pub fn matches(&self, log: &Log) -> bool {
+		if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+			return false;
+		}
+		if self.topics.len() > log.topics.len() {
+			return false;
+		}
+		self.topics
+			.iter()
+			.zip(&log.topics)
+			.all(|(alternatives, topic)| alternatives.is_empty() || alternatives.contains(topic))
+	}
+
+	/// Whether a block or receipt with `bloom` may contain matching logs. A `false` result is
+	/// definitive; a `true` result may be a false positive.
+	pub fn matches_bloom(&self, bloom: &Bloom) -> bool {
+		let contains = |bytes: &[u8]| bloom.contains_input(BloomInput::Raw(bytes));
+		if !self.addresses.is_empty() && !self.addresses.iter().any(|a| contains(&a[..])) {
+			return false;
+		}
+		self.topics.iter().all(|alternatives| {
+			alternatives.is_empty() || alternatives.iter().any(|t| contains(&t[..]))
+		})
+	}
+
+	/// Whether the block of `header` is in range and may contain matching logs, so that its
+	/// receipts need to be read.
+	pub fn matches_header(&self, header: &Header) -> bool {
+		self.matches_block_number(header.number) && self.matches_bloom(&header.logs_bloom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloc::vec;
+	use hex_literal::hex;
+	use sha3::{Digest, Keccak256};
+
+	fn log(address: u8, topics: &[u8]) -> Log {
+		Log {
+			address: H160::repeat_byte(address),
+			topics: topics.iter().map(|t| H256::repeat_byte(*t)).collect(),
+			data: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn matches_addresses_and_positional_topics() {
+		let any = LogFilter::new();
+		assert!(any.matches(&log(1, &[])));
+
+		let filter = LogFilter::new()
+			.address(H160::repeat_byte(1))
+			.address(H160::repeat_byte(2))
+			.topic(1, vec![H256::repeat_byte(0xb), H256::repeat_byte(0xc)]);
+		assert_eq!(filter.topics[0], Vec::new());
+		assert!(filter.matches(&log(1, &[0xa, 0xb])));
+		assert!(filter.matches(&log(2, &[0xf, 0xc, 0xd])));
+		assert!(!filter.matches(&log(3, &[0xa, 0xb])));
+		assert!(!filter.matches(&log(1, &[0xb, 0xa])));
+		// A log with fewer topics than the filter never matches, even on a wildcard.
+		assert!(!filter.matches(&log(1, &[0xa])));
+		assert!(!LogFilter::new().topic(0, vec![]).matches(&log(1, &[])));
+	}
+
+	#[test]
+	fn prechecks_block_range_and_bloom() {
+		let logs = [log(1, &[0xa, 0xb]), log(2, &[0xc])];
+		let header = Header {
+			parent_hash: H256::zero(),
+			ommers_hash: H256::zero(),
+			beneficiary: H160::zero(),
+			state_root: H256::zero(),
+			transactions_root: H256::zero(),
+			receipts_root: H256::zero(),
+			logs_bloom: logs_bloom(&logs),
+			difficulty: U256::zero(),
+			number: 100.into(),
+			gas_limit: U256::zero(),
+			gas_used: U256::zero(),
+			timestamp: 0,
+			extra_data: Vec::new(),
+			mix_hash: H256::zero(),
+			nonce: Default::default(),
+			base_fee: None,
+			withdrawals_root: None,
+			blob_gas_used: None,
+			excess_blob_gas: None,
+			parent_beacon_block_root: None,
+			requests_hash: None,
+		};
+
+		let filter = LogFilter::new()
+			.from_block(100)
+			.to_block(200)
+			.address(H160::repeat_byte(2))
+			.topic(0, vec![H256::repeat_byte(0xc)]);
+		assert!(filter.matches_header(&header));
+		assert!(!filter.clone().from_block(101).matches_header(&header));
+		assert!(!filter.clone().to_block(99).matches_header(&header));
+		assert!(!filter
+			.clone()
+			.address(H160::repeat_byte(3))
+			.topic(0, vec![H256::repeat_byte(0xd)])
+			.matches_header(&header));
+		assert!(!LogFilter::new()
+			.address(H160::repeat_byte(3))
+			.matches_bloom(&header.logs_bloom));
+		assert!(LogFilter::new().matches_header(&header));
+	}
+
+	/// Bloom of `items` built as in the Yellow Paper: each item sets the three bits selected by
+	/// the low 11 bits of the first three byte pairs of its Keccak-256 hash.
+	fn yellow_paper_bloom(items: &[&[u8]]) -> Bloom {
+		let mut bloom = [0u8; 256];
+		for item in items {
+			let hash = Keccak256::digest(item);
+			for pair in hash[..6].chunks(2) {
+				let bit = (usize::from(pair[0]) << 8 | usize::from(pair[1])) & 2047;
+				bloom[255 - bit / 8] |= 1 << (bit % 8);
+			}
+		}
+		Bloom::from(bloom)
+	}
+
+	#[test]
+	fn builds_yellow_paper_bloom() {
+		// An ERC-20 `Transfer` log of the mainnet USDT contract.
+		let transfer = H256::from_slice(&Keccak256::digest(b"Transfer(address,address,uint256)"));
+		assert_eq!(
+			transfer,
+			H256::from(hex!(
+				"ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+			))
+		);
+		let log = Log {
+			address: hex!("dac17f958d2ee523a2206206994597c13d831ec7").into(),
+			topics: vec![
+				transfer,
+				H256::from(H160::repeat_byte(0xaa)),
+				H256::from(H160::repeat_byte(0xbb)),
+			],
+			data: H256::from_low_u64_be(1_000_000).as_bytes().to_vec(),
+		};
+
+		let bloom = logs_bloom([&log]);
+		assert_eq!(
+			bloom,
+			yellow_paper_bloom(&[
+				log.address.as_bytes(),
+				log.topics[0].as_bytes(),
+				log.topics[1].as_bytes(),
+				log.topics[2].as_bytes(),
+			])
+		);
+		assert!((1..=12).contains(&bloom.as_bytes().iter().map(|b| b.count_ones()).sum::<u32>()));
+		assert_eq!(logs_bloom(&[]), Bloom::zero());
+	}
+}
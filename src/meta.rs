@@ -0,0 +1,385 @@
+//! Transactions and receipts annotated with where they were included in the chain.
+
+use crate::{
+	Block, EnvelopedEncodable, Log, Receipt, ReceiptV2, ReceiptV3, ReceiptV4, ReceiptV5,
+	TransactionV1, TransactionV2, TransactionV3, TransactionV4,
+};
+use alloc::vec::Vec;
+use ethereum_types::{Address, H256, U256};
+
+/// Transaction with its hash and position in a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransactionWithMeta<T> {
+	pub transaction: T,
+	pub hash: H256,
+	/// Sender, if it was supplied to the builder.
+	pub from: Option<Address>,
+	pub block_hash: H256,
+	pub block_number: U256,
+	pub transaction_index: u64,
+}
+
+/// Receipt with the transaction it belongs to, its own gas usage and the block-wide index of
+/// its first log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReceiptWithMeta<R> {
+	pub receipt: R,
+	pub transaction_hash: H256,
+	pub block_hash: H256,
+	pub block_number: U256,
+	pub transaction_index: u64,
+	/// Gas used by this transaction alone, unlike the receipt's cumulative `used_gas`.
+	pub gas_used: U256,
+	pub effective_gas_price: U256,
+	/// Address of the created contract, if the transaction creates one and its sender was
+	/// supplied to the builder.
+	pub contract_address: Option<Address>,
+	/// Index in the block of the receipt's first log.
+	pub log_index: u64,
+}
+
+/// Error returned when receipts or senders do not line up with a block's transactions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MetaError {
+	/// The number of receipts differs from the number of transactions.
+	ReceiptCountMismatch {
+		transactions: usize,
+		receipts: usize,
+	},
+	/// The number of senders differs from the number of transactions.
+	SenderCountMismatch { transactions: usize, senders: usize },
+	/// The cumulative gas of the receipt at `index` is below that of the previous receipt.
+	CumulativeGasDecreased { index: usize },
+	/// The receipt at `index` is of a different type than its transaction.
+	ReceiptTypeMismatch { index: usize },
+}
+
+impl<R: Receipt> ReceiptWithMeta<R> {
+	/// Logs of the receipt with their index in the block.
+	pub fn logs(&self) -> impl Iterator<Item = (u64, &Log)> {
+		let first = self.log_index;
+		self.receipt
+			.logs()
+			.iter()
+			.enumerate()
+			.map(move |(i, log)| (first + i as u64, log))
+	}
+}
+
+/// Per-type operations the builder needs from a transaction.
+struct TransactionOps<T> {
+	hash: fn(&T) -> H256,
+	effective_gas_price: fn(&T, U256) -> U256,
+	created_address: fn(&T, Address) -> Option<Address>,
+}
+
+/// Transactions and receipts of a block with their metadata.
+type WithMeta<T, R> = (Vec<TransactionWithMeta<T>>, Vec<ReceiptWithMeta<R>>);
+
+fn with_meta<T, R>(
+	block: &Block<T>,
+	receipts: Vec<R>,
+	senders: Option<&[Address]>,
+	ops: TransactionOps<T>,
+) -> Result<WithMeta<T, R>, MetaError>
+where
+	T: Clone + EnvelopedEncodable,
+	R: Receipt + EnvelopedEncodable,
+{
+	let count = block.transactions.len();
+	if receipts.len() != count {
+		return Err(MetaError::ReceiptCountMismatch {
+			transactions: count,
+			receipts: receipts.len(),
+		});
+	}
+	if let Some(senders) = senders {
+		if senders.len() != count {
+			return Err(MetaError::SenderCountMismatch {
+				transactions: count,
+				senders: senders.len(),
+			});
+		}
+	}
+
+	let block_hash = block.header.hash();
+	let block_number = block.header.number;
+	let base_fee = block.header.base_fee.unwrap_or_default();
+
+	let mut transactions = Vec::with_capacity(count);
+	let mut receipts_with_meta = Vec::with_capacity(count);
+	let mut cumulative_gas = U256::zero();
+	let mut log_index = 0;
+	for (index, (transaction, receipt)) in block.transactions.iter().zip(receipts).enumerate() {
+		if receipt.type_id() != transaction.type_id() {
+			return Err(MetaError::ReceiptTypeMismatch { index });
+		}
+		let used_gas = receipt.used_gas();
+		let gas_used = used_gas
+			.checked_sub(cumulative_gas)
+			.ok_or(MetaError::CumulativeGasDecreased { index })?;
+		cumulative_gas = used_gas;
+		let logs = receipt.logs().len() as u64;
+
+		let hash = (ops.hash)(transaction);
+		let from = senders.map(|senders| senders[index]);
+		transactions.push(TransactionWithMeta {
+			transaction: transaction.clone(),
+			hash,
+			from,
+			block_hash,
+			block_number,
+			transaction_index: index as u64,
+		});
+		receipts_with_meta.push(ReceiptWithMeta {
+			receipt,
+			transaction_hash: hash,
+			block_hash,
+			block_number,
+			transaction_index: index as u64,
+			gas_used,
+			effective_gas_price: (ops.effective_gas_price)(transaction, base_fee),
+			contract_address: from.and_then(|from| (ops.created_address)(transaction, from)),
+			log_index,
+		});
+		log_index += logs;
+	}
+
+	Ok((transactions, receipts_with_meta))
+}
+
+macro_rules! impl_block_with_meta {
+	($($tx:ty => $receipt:ty),*) => {
+		$(
+			impl Block<$tx> {
+				/// Annotate the block's transactions and their `receipts` with inclusion
+				/// metadata. `senders`, if known, must list the sender of each transaction.
+				pub fn with_meta(
+					&self,
+					receipts: Vec<$receipt>,
+					senders: Option<&[Address]>,
+				) -> Result<WithMeta<$tx, $receipt>, MetaError> {
+					with_meta(
+						self,
+						receipts,
+						senders,
+						TransactionOps {
+							hash: <$tx>::hash,
+							effective_gas_price: <$tx>::effective_gas_price,
+							created_address: <$tx>::created_address,
+						},
+					)
+				}
+			}
+		)*
+	};
+}
+
+impl_block_with_meta!(
+	TransactionV1 => ReceiptV2,
+	TransactionV2 => ReceiptV3,
+	TransactionV3 => ReceiptV4,
+	TransactionV4 => ReceiptV5
+);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		util::contract_address, EIP1559Transaction, EIP4844Transaction, EIP658ReceiptData,
+		LegacyTransaction, PartialHeader, TransactionAction, TransactionSignature,
+	};
+	use alloc::vec;
+	use ethereum_types::{Bloom, H64};
+
+	fn log(byte: u8) -> Log {
+		Log {
+			address: Address::repeat_byte(byte),
+			topics: vec![],
+			data: vec![],
+		}
+	}
+
+	fn receipt(used_gas: u64, logs: Vec<Log>) -> EIP658ReceiptData {
+		EIP658ReceiptData {
+			status_code: 1,
+			used_gas: used_gas.into(),
+			logs_bloom: Bloom::zero(),
+			logs,
+		}
+	}
+
+	fn block<T: From<TransactionV2> + EnvelopedEncodable>() -> Block<T> {
+		let create = TransactionV2::Legacy(LegacyTransaction {
+			nonce: 3.into(),
+			gas_price: 20.into(),
+			gas_limit: 100_000.into(),
+			action: TransactionAction::Create,
+			value: 0.into(),
+			input: vec![0x60, 0x00],
+			signature: TransactionSignature::new(27, H256::repeat_byte(1), H256::repeat_byte(1))
+				.unwrap(),
+		});
+		let call = TransactionV2::EIP1559(EIP1559Transaction {
+			chain_id: 1,
+			nonce: 0.into(),
+			max_priority_fee_per_gas: 2.into(),
+			max_fee_per_gas: 30.into(),
+			gas_limit: 50_000.into(),
+			action: TransactionAction::Call(Address::repeat_byte(0x35)),
+			value: 0.into(),
+			input: vec![],
+			access_list: vec![],
+			odd_y_parity: false,
+			r: H256::repeat_byte(2),
+			s: H256::repeat_byte(2),
+		});
+		Block::new(
+			PartialHeader {
+				parent_hash: H256::zero(),
+				beneficiary: Address::zero(),
+				state_root: H256::zero(),
+				receipts_root: H256::zero(),
+				logs_bloom: Bloom::zero(),
+				difficulty: U256::zero(),
+				number: 42.into(),
+				gas_limit: 30_000_000.into(),
+				gas_used: 90_000.into(),
+				timestamp: 0,
+				extra_data: vec![],
+				mix_hash: H256::zero(),
+				nonce: H64::zero(),
+				base_fee: Some(10.into()),
+				blob_gas_used: None,
+				excess_blob_gas: None,
+				parent_beacon_block_root: None,
+				requests_hash: None,
+			},
+			vec![create.into(), call.into()],
+			vec![],
+			None,
+		)
+	}
+
+	#[test]
+	fn derives_gas_log_indices_and_contract_address() {
+		let block = block::<TransactionV2>();
+		let senders = [Address::repeat_byte(0xaa), Address::repeat_byte(0xbb)];
+		let receipts = vec![
+			ReceiptV3::Legacy(receipt(60_000, vec![log(1), log(2)])),
+			ReceiptV3::EIP1559(receipt(90_000, vec![log(3)])),
+		];
+
+		let (transactions, receipts) = block.with_meta(receipts, Some(&senders)).unwrap();
+
+		assert_eq!(transactions[1].hash, block.transactions[1].hash());
+		assert_eq!(transactions[1].from, Some(senders[1]));
+		assert_eq!(transactions[1].transaction_index, 1);
+		assert_eq!(transactions[1].block_hash, block.header.hash());
+
+		assert_eq!(receipts[0].gas_used, 60_000.into());
+		assert_eq!(receipts[1].gas_used, 30_000.into());
+		assert_eq!(receipts[0].effective_gas_price, 20.into());
+		assert_eq!(receipts[1].effective_gas_price, 12.into());
+		assert_eq!(
+			receipts[0].contract_address,
+			Some(contract_address(senders[0], 3.into()))
+		);
+		assert_eq!(receipts[1].contract_address, None);
+		assert_eq!(receipts[1].transaction_hash, transactions[1].hash);
+		assert_eq!(
+			receipts
+				.iter()
+				.flat_map(|r| r.logs().map(|(index, log)| (index, log.address)))
+				.collect::<Vec<_>>(),
+			vec![
+				(0, Address::repeat_byte(1)),
+				(1, Address::repeat_byte(2)),
+				(2, Address::repeat_byte(3)),
+			]
+		);
+	}
+
+	#[test]
+	fn rejects_inconsistent_receipts() {
+		let block = block::<TransactionV2>();
+		let legacy = |used_gas| ReceiptV3::Legacy(receipt(used_gas, vec![]));
+		let eip1559 = |used_gas| ReceiptV3::EIP1559(receipt(used_gas, vec![]));
+		assert_eq!(
+			block.with_meta(vec![legacy(1)], None),
+			Err(MetaError::ReceiptCountMismatch {
+				transactions: 2,
+				receipts: 1
+			})
+		);
+		assert_eq!(
+			block.with_meta(vec![legacy(2), eip1559(1)], None),
+			Err(MetaError::CumulativeGasDecreased { index: 1 })
+		);
+		assert_eq!(
+			block.with_meta(vec![legacy(1), eip1559(2)], Some(&[Address::zero()])),
+			Err(MetaError::SenderCountMismatch {
+				transactions: 2,
+				senders: 1
+			})
+		);
+
+		assert_eq!(
+			block.with_meta(vec![legacy(1), legacy(2)], None),
+			Err(MetaError::ReceiptTypeMismatch { index: 1 })
+		);
+
+		let (_, receipts) = block.with_meta(vec![legacy(1), eip1559(2)], None).unwrap();
+		assert_eq!(receipts[0].contract_address, None);
+	}
+
+	#[test]
+	fn pairs_blob_transactions_with_their_receipts() {
+		let mut block = block::<TransactionV4>();
+		block
+			.transactions
+			.push(TransactionV4::EIP4844(EIP4844Transaction {
+				chain_id: 1,
+				nonce: 1.into(),
+				max_priority_fee_per_gas: 2.into(),
+				max_fee_per_gas: 30.into(),
+				gas_limit: 50_000.into(),
+				to: Address::repeat_byte(0x48),
+				value: 0.into(),
+				input: vec![],
+				access_list: vec![],
+				max_fee_per_blob_gas: 1.into(),
+				blob_versioned_hashes: vec![H256::repeat_byte(1)],
+				odd_y_parity: false,
+				r: H256::repeat_byte(3),
+				s: H256::repeat_byte(3),
+			}));
+		let receipts = vec![
+			ReceiptV5::Legacy(receipt(60_000, vec![])),
+			ReceiptV5::EIP1559(receipt(90_000, vec![log(1)])),
+			ReceiptV5::EIP4844(receipt(111_000, vec![log(2)])),
+		];
+
+		let (transactions, receipts) = block.with_meta(receipts, None).unwrap();
+		assert_eq!(transactions[2].hash, block.transactions[2].hash());
+		assert_eq!(receipts[2].gas_used, 21_000.into());
+		assert_eq!(receipts[2].effective_gas_price, 12.into());
+		assert_eq!(
+			receipts[2]
+				.logs()
+				.map(|(index, _)| index)
+				.collect::<Vec<_>>(),
+			vec![1]
+		);
+	}
+}
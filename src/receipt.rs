@@ -1,4 +1,7 @@
-use crate::{EnvelopedDecodable, EnvelopedDecoderError, EnvelopedEncodable, Log};
+use crate::{
+	util::ordered_trie_root, DecodeError, EnvelopedDecodable, EnvelopedDecoderError,
+	EnvelopedEncodable, Fork, Log,
+};
 use alloc::vec::Vec;
 use bytes::BytesMut;
 use ethereum_types::{Bloom, H256, U256};
@@ -36,6 +39,10 @@ pub type EIP2930ReceiptData = EIP658ReceiptData;
 
 pub type EIP1559ReceiptData = EIP658ReceiptData;
 
+pub type EIP4844ReceiptData = EIP658ReceiptData;
+
+pub type EIP7702ReceiptData = EIP658ReceiptData;
+
 pub type ReceiptV0 = FrontierReceiptData;
 
 impl EnvelopedEncodable for ReceiptV0 {
@@ -48,10 +55,10 @@ impl EnvelopedEncodable for ReceiptV0 {
 }
 
 impl EnvelopedDecodable for ReceiptV0 {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
 
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
-		Ok(rlp::decode(bytes)?)
+		Ok(rlp::decode::<Self>(bytes)?)
 	}
 }
 
@@ -67,10 +74,10 @@ impl EnvelopedEncodable for ReceiptV1 {
 }
 
 impl EnvelopedDecodable for ReceiptV1 {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
 
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
-		Ok(rlp::decode(bytes)?)
+		Ok(rlp::decode::<Self>(bytes)?)
 	}
 }
 
@@ -104,11 +111,11 @@ impl EnvelopedEncodable for ReceiptV2 {
 }
 
 impl EnvelopedDecodable for ReceiptV2 {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
 
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
 		if bytes.is_empty() {
-			return Err(EnvelopedDecoderError::UnknownTypeId);
+			return Err(DecoderError::RlpIsTooShort.into());
 		}
 
 		let first = bytes[0];
@@ -124,7 +131,7 @@ impl EnvelopedDecodable for ReceiptV2 {
 			return Ok(Self::EIP2930(rlp::decode(s)?));
 		}
 
-		Err(DecoderError::Custom("invalid receipt type").into())
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
 	}
 }
 
@@ -171,11 +178,11 @@ impl EnvelopedEncodable for ReceiptV3 {
 }
 
 impl EnvelopedDecodable for ReceiptV3 {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
 
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
 		if bytes.is_empty() {
-			return Err(EnvelopedDecoderError::UnknownTypeId);
+			return Err(DecoderError::RlpIsTooShort.into());
 		}
 
 		let first = bytes[0];
@@ -195,7 +202,7 @@ impl EnvelopedDecodable for ReceiptV3 {
 			return Ok(Self::EIP1559(rlp::decode(s)?));
 		}
 
-		Err(DecoderError::Custom("invalid receipt type").into())
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
 	}
 }
 
@@ -209,6 +216,177 @@ impl From<ReceiptV3> for EIP658ReceiptData {
 	}
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReceiptV4 {
+	/// Legacy receipt type
+	Legacy(EIP658ReceiptData),
+	/// EIP-2930 receipt type
+	EIP2930(EIP2930ReceiptData),
+	/// EIP-1559 receipt type
+	EIP1559(EIP1559ReceiptData),
+	/// EIP-4844 receipt type
+	EIP4844(EIP4844ReceiptData),
+}
+
+impl EnvelopedEncodable for ReceiptV4 {
+	fn type_id(&self) -> Option<u8> {
+		match self {
+			Self::Legacy(_) => None,
+			Self::EIP2930(_) => Some(1),
+			Self::EIP1559(_) => Some(2),
+			Self::EIP4844(_) => Some(3),
+		}
+	}
+
+	fn encode_payload(&self) -> BytesMut {
+		match self {
+			Self::Legacy(r) => rlp::encode(r),
+			Self::EIP2930(r) => rlp::encode(r),
+			Self::EIP1559(r) => rlp::encode(r),
+			Self::EIP4844(r) => rlp::encode(r),
+		}
+	}
+}
+
+impl EnvelopedDecodable for ReceiptV4 {
+	type PayloadDecoderError = DecodeError;
+
+	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
+		if bytes.is_empty() {
+			return Err(DecoderError::RlpIsTooShort.into());
+		}
+
+		let first = bytes[0];
+
+		let rlp = Rlp::new(bytes);
+		if rlp.is_list() {
+			return Ok(Self::Legacy(Decodable::decode(&rlp)?));
+		}
+
+		let s = &bytes[1..];
+
+		if first == 0x01 {
+			return Ok(Self::EIP2930(rlp::decode(s)?));
+		}
+
+		if first == 0x02 {
+			return Ok(Self::EIP1559(rlp::decode(s)?));
+		}
+
+		if first == 0x03 {
+			return Ok(Self::EIP4844(rlp::decode(s)?));
+		}
+
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
+	}
+}
+
+impl From<ReceiptV4> for EIP658ReceiptData {
+	fn from(v4: ReceiptV4) -> Self {
+		match v4 {
+			ReceiptV4::Legacy(r) => r,
+			ReceiptV4::EIP2930(r) => r,
+			ReceiptV4::EIP1559(r) => r,
+			ReceiptV4::EIP4844(r) => r,
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReceiptV5 {
+	/// Legacy receipt type
+	Legacy(EIP658ReceiptData),
+	/// EIP-2930 receipt type
+	EIP2930(EIP2930ReceiptData),
+	/// EIP-1559 receipt type
+	EIP1559(EIP1559ReceiptData),
+	/// EIP-4844 receipt type
+	EIP4844(EIP4844ReceiptData),
+	/// EIP-7702 receipt type
+	EIP7702(EIP7702ReceiptData),
+}
+
+impl EnvelopedEncodable for ReceiptV5 {
+	fn type_id(&self) -> Option<u8> {
+		match self {
+			Self::Legacy(_) => None,
+			Self::EIP2930(_) => Some(1),
+			Self::EIP1559(_) => Some(2),
+			Self::EIP4844(_) => Some(3),
+			Self::EIP7702(_) => Some(4),
+		}
+	}
+
+	fn encode_payload(&self) -> BytesMut {
+		match self {
+			Self::Legacy(r) => rlp::encode(r),
+			Self::EIP2930(r) => rlp::encode(r),
+			Self::EIP1559(r) => rlp::encode(r),
+			Self::EIP4844(r) => rlp::encode(r),
+			Self::EIP7702(r) => rlp::encode(r),
+		}
+	}
+}
+
+impl EnvelopedDecodable for ReceiptV5 {
+	type PayloadDecoderError = DecodeError;
+
+	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
+		if bytes.is_empty() {
+			return Err(DecoderError::RlpIsTooShort.into());
+		}
+
+		let first = bytes[0];
+
+		let rlp = Rlp::new(bytes);
+		if rlp.is_list() {
+			return Ok(Self::Legacy(Decodable::decode(&rlp)?));
+		}
+
+		let s = &bytes[1..];
+
+		if first == 0x01 {
+			return Ok(Self::EIP2930(rlp::decode(s)?));
+		}
+
+		if first == 0x02 {
+			return Ok(Self::EIP1559(rlp::decode(s)?));
+		}
+
+		if first == 0x03 {
+			return Ok(Self::EIP4844(rlp::decode(s)?));
+		}
+
+		if first == 0x04 {
+			return Ok(Self::EIP7702(rlp::decode(s)?));
+		}
+
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
+	}
+}
+
+impl From<ReceiptV5> for EIP658ReceiptData {
+	fn from(v5: ReceiptV5) -> Self {
+		match v5 {
+			ReceiptV5::Legacy(r) => r,
+			ReceiptV5::EIP2930(r) => r,
+			ReceiptV5::EIP1559(r) => r,
+			ReceiptV5::EIP4844(r) => r,
+			ReceiptV5::EIP7702(r) => r,
+		}
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
 	feature = "with-codec",
@@ -224,6 +402,10 @@ pub enum ReceiptAny {
 	EIP2930(EIP2930ReceiptData),
 	/// EIP-1559 receipt type
 	EIP1559(EIP1559ReceiptData),
+	/// EIP-4844 receipt type
+	EIP4844(EIP4844ReceiptData),
+	/// EIP-7702 receipt type
+	EIP7702(EIP7702ReceiptData),
 }
 
 impl EnvelopedEncodable for ReceiptAny {
@@ -233,6 +415,8 @@ impl EnvelopedEncodable for ReceiptAny {
 			Self::EIP658(_) => None,
 			Self::EIP2930(_) => Some(1),
 			Self::EIP1559(_) => Some(2),
+			Self::EIP4844(_) => Some(3),
+			Self::EIP7702(_) => Some(4),
 		}
 	}
 
@@ -242,44 +426,318 @@ impl EnvelopedEncodable for ReceiptAny {
 			Self::EIP658(r) => rlp::encode(r),
 			Self::EIP2930(r) => rlp::encode(r),
 			Self::EIP1559(r) => rlp::encode(r),
+			Self::EIP4844(r) => rlp::encode(r),
+			Self::EIP7702(r) => rlp::encode(r),
+		}
+	}
+}
+
+impl ReceiptAny {
+	/// Decode a receipt of a block in `fork`.
+	///
+	/// Unlike [`EnvelopedDecodable::decode`], legacy receipts are classified by the fork rather
+	/// than by the shape of their first field, and typed receipts not yet valid in `fork` are
+	/// rejected as unknown.
+	pub fn decode_for_fork(
+		bytes: &[u8],
+		fork: Fork,
+	) -> Result<Self, EnvelopedDecoderError<DecodeError>> {
+		if bytes.is_empty() {
+			return Err(DecoderError::RlpIsTooShort.into());
+		}
+
+		let rlp = Rlp::new(bytes);
+		if rlp.is_list() {
+			return if fork.has_receipt_status() {
+				Ok(Self::EIP658(Decodable::decode(&rlp)?))
+			} else {
+				Ok(Self::Frontier(Decodable::decode(&rlp)?))
+			};
+		}
+
+		if !fork.allows_tx_type(Some(bytes[0])) {
+			return Err(EnvelopedDecoderError::UnknownTypeId(bytes[0]));
+		}
+		Self::decode_typed(bytes)
+	}
+
+	fn decode_typed(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<DecodeError>> {
+		let first = bytes[0];
+		let s = &bytes[1..];
+
+		match first {
+			0x01 => Ok(Self::EIP2930(rlp::decode(s)?)),
+			0x02 => Ok(Self::EIP1559(rlp::decode(s)?)),
+			0x03 => Ok(Self::EIP4844(rlp::decode(s)?)),
+			0x04 => Ok(Self::EIP7702(rlp::decode(s)?)),
+			_ => Err(EnvelopedDecoderError::UnknownTypeId(first)),
 		}
 	}
 }
 
 impl EnvelopedDecodable for ReceiptAny {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
 
+	/// Decode a receipt without knowing its fork. Legacy receipts whose first field is a
+	/// 32-byte state root decode as [`ReceiptAny::Frontier`], and those with a status code
+	/// as [`ReceiptAny::EIP658`].
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
 		if bytes.is_empty() {
-			return Err(EnvelopedDecoderError::UnknownTypeId);
+			return Err(DecoderError::RlpIsTooShort.into());
 		}
 
-		let first = bytes[0];
-
 		let rlp = Rlp::new(bytes);
 		if rlp.is_list() {
-			if rlp.item_count()? == 4 {
-				let first = rlp.at(0)?;
-				if first.is_data() && first.data()?.len() <= 1 {
-					return Ok(Self::Frontier(Decodable::decode(&rlp)?));
-				} else {
-					return Ok(Self::EIP658(Decodable::decode(&rlp)?));
-				}
+			if rlp.item_count()? != 4 {
+				return Err(DecoderError::RlpIncorrectListLen.into());
 			}
 
-			return Err(DecoderError::RlpIncorrectListLen.into());
+			let first = rlp.at(0)?;
+			return if first.is_data() && first.data()?.len() == 32 {
+				Ok(Self::Frontier(Decodable::decode(&rlp)?))
+			} else {
+				Ok(Self::EIP658(Decodable::decode(&rlp)?))
+			};
 		}
 
-		let s = &bytes[1..];
+		Self::decode_typed(bytes)
+	}
+}
 
-		if first == 0x01 {
-			return Ok(Self::EIP2930(rlp::decode(s)?));
-		}
+/// Fields shared by every receipt type.
+pub trait Receipt {
+	/// Cumulative gas used in the block up to and including this transaction.
+	fn used_gas(&self) -> U256;
+	/// Bloom filter of the logs in this receipt.
+	fn logs_bloom(&self) -> &Bloom;
+	/// Logs emitted by the transaction.
+	fn logs(&self) -> &[Log];
+}
 
-		if first == 0x02 {
-			return Ok(Self::EIP1559(rlp::decode(s)?));
+macro_rules! impl_receipt {
+	($($receipt:ty),*) => {
+		$(
+			impl Receipt for $receipt {
+				fn used_gas(&self) -> U256 {
+					self.used_gas
+				}
+
+				fn logs_bloom(&self) -> &Bloom {
+					&self.logs_bloom
+				}
+
+				fn logs(&self) -> &[Log] {
+					&self.logs
+				}
+			}
+		)*
+	};
+}
+
+impl_receipt!(FrontierReceiptData, EIP658ReceiptData);
+
+macro_rules! impl_enum_receipt {
+	($($enum:ident { $($variant:ident),* }),*) => {
+		$(
+			impl Receipt for $enum {
+				fn used_gas(&self) -> U256 {
+					match self {
+						$($enum::$variant(r) => r.used_gas(),)*
+					}
+				}
+
+				fn logs_bloom(&self) -> &Bloom {
+					match self {
+						$($enum::$variant(r) => r.logs_bloom(),)*
+					}
+				}
+
+				fn logs(&self) -> &[Log] {
+					match self {
+						$($enum::$variant(r) => r.logs(),)*
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_enum_receipt!(
+	ReceiptV2 { Legacy, EIP2930 },
+	ReceiptV3 {
+		Legacy,
+		EIP2930,
+		EIP1559
+	},
+	ReceiptV4 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844
+	},
+	ReceiptV5 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844,
+		EIP7702
+	},
+	ReceiptAny {
+		Frontier,
+		EIP658,
+		EIP2930,
+		EIP1559,
+		EIP4844,
+		EIP7702
+	}
+);
+
+/// Compute the receipts root of a block from its receipts, in transaction order.
+pub fn receipts_root<R: EnvelopedEncodable>(receipts: &[R]) -> H256 {
+	ordered_trie_root(
+		receipts
+			.iter()
+			.map(|r| EnvelopedEncodable::encode(r).freeze()),
+	)
+}
+
+/// Compute the block-level `logs_bloom` by combining the blooms of all receipts.
+pub fn receipts_logs_bloom<R: Receipt>(receipts: &[R]) -> Bloom {
+	let mut bloom = Bloom::zero();
+	for receipt in receipts {
+		bloom.accrue_bloom(receipt.logs_bloom());
+	}
+	bloom
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::logs_bloom;
+	use alloc::vec;
+	use ethereum_types::{BloomInput, H160};
+	use hex_literal::hex;
+
+	fn receipt(used_gas: u64, logs: Vec<Log>) -> EIP658ReceiptData {
+		EIP658ReceiptData {
+			status_code: 1,
+			used_gas: used_gas.into(),
+			logs_bloom: logs_bloom(&logs),
+			logs,
 		}
+	}
+
+	#[test]
+	fn computes_mainnet_receipts_roots() {
+		assert_eq!(
+			receipts_root::<ReceiptV3>(&[]),
+			H256::from(hex!(
+				"56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+			))
+		);
+
+		// Receipts roots of post-Byzantium mainnet blocks whose only transaction is a
+		// successful plain transfer, as a legacy and as an EIP-1559 transaction.
+		let transfer = receipt(21_000, vec![]);
+		let legacy = ReceiptV3::Legacy(transfer.clone());
+		let mut encoded = hex!("f9010801825208b90100").to_vec();
+		encoded.extend_from_slice(&[0; 256]);
+		encoded.push(0xc0);
+		assert_eq!(EnvelopedEncodable::encode(&legacy).to_vec(), encoded);
+		assert_eq!(
+			<ReceiptV3 as EnvelopedDecodable>::decode(&encoded),
+			Ok(legacy.clone())
+		);
+		assert_eq!(
+			receipts_root(&[legacy]),
+			H256::from(hex!(
+				"056b23fbba480696b65fe5a59b8f2148a1299103c4f57df839233af2cf4ca2d2"
+			))
+		);
+
+		let eip1559 = ReceiptV3::EIP1559(transfer);
+		encoded.insert(0, 0x02);
+		assert_eq!(EnvelopedEncodable::encode(&eip1559).to_vec(), encoded);
+		assert_eq!(
+			receipts_root(&[eip1559]),
+			H256::from(hex!(
+				"f78dfb743fbd92ade140711c8bbc542b5e307f0ab7984eff35d751969fe57efa"
+			))
+		);
+	}
+
+	#[test]
+	fn accrues_block_bloom() {
+		// ERC-20 `Transfer(address,address,uint256)` event signature.
+		let transfer = H256::from(hex!(
+			"ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+		));
+		let token = H160::from(hex!("dac17f958d2ee523a2206206994597c13d831ec7"));
+		let receipts = vec![
+			ReceiptV3::Legacy(receipt(21_000, vec![])),
+			ReceiptV3::EIP1559(receipt(
+				72_000,
+				vec![Log {
+					address: token,
+					topics: vec![transfer],
+					data: vec![],
+				}],
+			)),
+		];
+
+		let bloom = receipts_logs_bloom(&receipts);
+		assert_eq!(bloom, logs_bloom(receipts[1].logs()));
+		assert!(bloom.contains_input(BloomInput::Raw(token.as_bytes())));
+		assert!(bloom.contains_input(BloomInput::Raw(transfer.as_bytes())));
+		assert!(!bloom.contains_input(BloomInput::Raw(&[0x33; 20])));
+	}
 
-		Err(DecoderError::Custom("invalid receipt type").into())
+	#[test]
+	fn distinguishes_frontier_and_eip658_receipts() {
+		let frontier = FrontierReceiptData {
+			state_root: H256::repeat_byte(0x5e),
+			used_gas: 21_000.into(),
+			logs_bloom: Bloom::zero(),
+			logs: vec![],
+		};
+		let frontier_bytes = rlp::encode(&frontier);
+		let success_bytes = rlp::encode(&receipt(21_000, vec![]));
+		let failure_bytes = rlp::encode(&EIP658ReceiptData {
+			status_code: 0,
+			..receipt(21_000, vec![])
+		});
+
+		assert_eq!(
+			<ReceiptAny as EnvelopedDecodable>::decode(&frontier_bytes).unwrap(),
+			ReceiptAny::Frontier(frontier.clone())
+		);
+		assert_eq!(
+			<ReceiptAny as EnvelopedDecodable>::decode(&success_bytes).unwrap(),
+			ReceiptAny::EIP658(receipt(21_000, vec![]))
+		);
+		assert!(matches!(
+			<ReceiptAny as EnvelopedDecodable>::decode(&failure_bytes).unwrap(),
+			ReceiptAny::EIP658(EIP658ReceiptData { status_code: 0, .. })
+		));
+
+		assert_eq!(
+			ReceiptAny::decode_for_fork(&frontier_bytes, Fork::Homestead).unwrap(),
+			ReceiptAny::Frontier(frontier)
+		);
+		assert!(ReceiptAny::decode_for_fork(&success_bytes, Fork::Homestead).is_err());
+		assert_eq!(
+			ReceiptAny::decode_for_fork(&success_bytes, Fork::Byzantium).unwrap(),
+			ReceiptAny::EIP658(receipt(21_000, vec![]))
+		);
+
+		let typed = EnvelopedEncodable::encode(&ReceiptAny::EIP2930(receipt(21_000, vec![])));
+		assert_eq!(
+			ReceiptAny::decode_for_fork(&typed, Fork::Istanbul),
+			Err(EnvelopedDecoderError::UnknownTypeId(1))
+		);
+		assert!(matches!(
+			ReceiptAny::decode_for_fork(&typed, Fork::Berlin),
+			Ok(ReceiptAny::EIP2930(_))
+		));
 	}
 }
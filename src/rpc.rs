@@ -0,0 +1,1022 @@
+//! JSON-RPC representations of blocks, transactions, receipts and logs, in the shape returned by
+//! `eth_getBlockByNumber`, `eth_getTransactionByHash`, `eth_getTransactionReceipt` and
+//! `eth_getLogs`.
+
+use crate::{
+	engine::RawWithdrawal, AccessListItem, Authorization, EIP1559Transaction, EIP2930Transaction,
+	EIP4844Transaction, EIP658ReceiptData, EIP7702Transaction, FrontierReceiptData, Header,
+	LegacyTransaction, Log, ReceiptAny, TransactionAction, TransactionSignature, TransactionV4,
+	Withdrawal,
+};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use ethereum_types::{Address, Bloom, H256, H64, U256, U64};
+use serde::{Deserialize, Serialize};
+
+fn u256_to_h256(value: U256) -> H256 {
+	let mut bytes = [0_u8; 32];
+	value.to_big_endian(&mut bytes);
+	H256(bytes)
+}
+
+fn h256_to_u256(value: &H256) -> U256 {
+	U256::from_big_endian(&value[..])
+}
+
+fn y_parity(value: U64) -> Result<bool, &'static str> {
+	match value.as_u64() {
+		0 => Ok(false),
+		1 => Ok(true),
+		_ => Err("invalid y parity"),
+	}
+}
+
+/// Transaction as returned by `eth_getTransactionByHash` and in full blocks.
+///
+/// Deserialization checks `hash` against the decoded transaction; `from` is taken as given.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawTransaction", into = "RawTransaction")]
+pub struct RpcTransaction {
+	pub hash: H256,
+	pub from: Address,
+	/// `None` while the transaction is pending, as are the other inclusion fields.
+	pub block_hash: Option<H256>,
+	pub block_number: Option<U256>,
+	pub transaction_index: Option<U256>,
+	pub transaction: TransactionV4,
+}
+
+impl RpcTransaction {
+	/// Wrap a pending transaction sent by `from`.
+	pub fn pending(transaction: TransactionV4, from: Address) -> Self {
+		Self {
+			hash: transaction.hash(),
+			from,
+			block_hash: None,
+			block_number: None,
+			transaction_index: None,
+			transaction,
+		}
+	}
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawAuthorization {
+	chain_id: U256,
+	address: Address,
+	nonce: U64,
+	y_parity: U64,
+	r: U256,
+	s: U256,
+}
+
+impl From<&Authorization> for RawAuthorization {
+	fn from(authorization: &Authorization) -> Self {
+		Self {
+			chain_id: authorization.chain_id,
+			address: authorization.address,
+			nonce: authorization.nonce.into(),
+			y_parity: authorization.y_parity.into(),
+			r: h256_to_u256(&authorization.r),
+			s: h256_to_u256(&authorization.s),
+		}
+	}
+}
+
+impl TryFrom<RawAuthorization> for Authorization {
+	type Error = &'static str;
+
+	fn try_from(raw: RawAuthorization) -> Result<Self, Self::Error> {
+		Ok(Self {
+			chain_id: raw.chain_id,
+			address: raw.address,
+			nonce: raw.nonce.as_u64(),
+			y_parity: y_parity(raw.y_parity)? as u8,
+			r: u256_to_h256(raw.r),
+			s: u256_to_h256(raw.s),
+		})
+	}
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransaction {
+	hash: H256,
+	from: Address,
+	block_hash: Option<H256>,
+	block_number: Option<U256>,
+	transaction_index: Option<U256>,
+	#[serde(rename = "type", default)]
+	tx_type: U64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	chain_id: Option<U64>,
+	nonce: U256,
+	gas: U256,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	gas_price: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_priority_fee_per_gas: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_fee_per_gas: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_fee_per_blob_gas: Option<U256>,
+	to: Option<Address>,
+	value: U256,
+	#[serde(with = "crate::serde_helpers::bytes", alias = "data")]
+	input: Vec<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	access_list: Option<Vec<AccessListItem>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	blob_versioned_hashes: Option<Vec<H256>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	authorization_list: Option<Vec<RawAuthorization>>,
+	v: U64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	y_parity: Option<U64>,
+	r: U256,
+	s: U256,
+}
+
+impl From<RpcTransaction> for RawTransaction {
+	fn from(tx: RpcTransaction) -> Self {
+		let mut raw = RawTransaction {
+			hash: tx.hash,
+			from: tx.from,
+			block_hash: tx.block_hash,
+			block_number: tx.block_number,
+			transaction_index: tx.transaction_index,
+			..Default::default()
+		};
+
+		let (odd_y_parity, r, s) = match tx.transaction {
+			TransactionV4::Legacy(t) => {
+				raw.chain_id = t.signature.chain_id().map(Into::into);
+				raw.nonce = t.nonce;
+				raw.gas = t.gas_limit;
+				raw.gas_price = Some(t.gas_price);
+				raw.to = action_to(t.action);
+				raw.value = t.value;
+				raw.input = t.input;
+				raw.v = t.signature.v().into();
+				raw.r = h256_to_u256(t.signature.r());
+				raw.s = h256_to_u256(t.signature.s());
+				return raw;
+			}
+			TransactionV4::EIP2930(t) => {
+				raw.tx_type = 1.into();
+				raw.chain_id = Some(t.chain_id.into());
+				raw.nonce = t.nonce;
+				raw.gas = t.gas_limit;
+				raw.gas_price = Some(t.gas_price);
+				raw.to = action_to(t.action);
+				raw.value = t.value;
+				raw.input = t.input;
+				raw.access_list = Some(t.access_list);
+				(t.odd_y_parity, t.r, t.s)
+			}
+			TransactionV4::EIP1559(t) => {
+				raw.tx_type = 2.into();
+				raw.chain_id = Some(t.chain_id.into());
+				raw.nonce = t.nonce;
+				raw.gas = t.gas_limit;
+				raw.max_priority_fee_per_gas = Some(t.max_priority_fee_per_gas);
+				raw.max_fee_per_gas = Some(t.max_fee_per_gas);
+				raw.to = action_to(t.action);
+				raw.value = t.value;
+				raw.input = t.input;
+				raw.access_list = Some(t.access_list);
+				(t.odd_y_parity, t.r, t.s)
+			}
+			TransactionV4::EIP4844(t) => {
+				raw.tx_type = 3.into();
+				raw.chain_id = Some(t.chain_id.into());
+				raw.nonce = t.nonce;
+				raw.gas = t.gas_limit;
+				raw.max_priority_fee_per_gas = Some(t.max_priority_fee_per_gas);
+				raw.max_fee_per_gas = Some(t.max_fee_per_gas);
+				raw.max_fee_per_blob_gas = Some(t.max_fee_per_blob_gas);
+				raw.to = Some(t.to);
+				raw.value = t.value;
+				raw.input = t.input;
+				raw.access_list = Some(t.access_list);
+				raw.blob_versioned_hashes = Some(t.blob_versioned_hashes);
+				(t.odd_y_parity, t.r, t.s)
+			}
+			TransactionV4::EIP7702(t) => {
+				raw.tx_type = 4.into();
+				raw.chain_id = Some(t.chain_id.into());
+				raw.nonce = t.nonce;
+				raw.gas = t.gas_limit;
+				raw.max_priority_fee_per_gas = Some(t.max_priority_fee_per_gas);
+				raw.max_fee_per_gas = Some(t.max_fee_per_gas);
+				raw.to = Some(t.to);
+				raw.value = t.value;
+				raw.input = t.input;
+				raw.access_list = Some(t.access_list);
+				raw.authorization_list =
+					Some(t.authorization_list.iter().map(Into::into).collect());
+				(t.odd_y_parity, t.r, t.s)
+			}
+		};
+
+		// Nodes report typed transaction parities both as `yParity` and, for older clients, as `v`.
+		raw.v = (odd_y_parity as u64).into();
+		raw.y_parity = Some(raw.v);
+		raw.r = h256_to_u256(&r);
+		raw.s = h256_to_u256(&s);
+		raw
+	}
+}
+
+fn action_to(action: TransactionAction) -> Option<Address> {
+	match action {
+		TransactionAction::Call(address) => Some(address),
+		TransactionAction::Create => None,
+	}
+}
+
+fn to_action(to: Option<Address>) -> TransactionAction {
+	to.map_or(TransactionAction::Create, TransactionAction::Call)
+}
+
+impl TryFrom<RawTransaction> for RpcTransaction {
+	type Error = &'static str;
+
+	fn try_from(raw: RawTransaction) -> Result<Self, Self::Error> {
+		let chain_id = raw.chain_id.map(|id| id.as_u64());
+		let typed_chain_id = chain_id.ok_or("missing chainId");
+		let odd_y_parity = y_parity(raw.y_parity.unwrap_or(raw.v));
+		let access_list = raw.access_list.unwrap_or_default();
+		let max_priority_fee_per_gas = raw
+			.max_priority_fee_per_gas
+			.ok_or("missing maxPriorityFeePerGas");
+		let max_fee_per_gas = raw.max_fee_per_gas.ok_or("missing maxFeePerGas");
+		let r = u256_to_h256(raw.r);
+		let s = u256_to_h256(raw.s);
+
+		let transaction = match raw.tx_type.as_u64() {
+			0 => TransactionV4::Legacy(LegacyTransaction {
+				nonce: raw.nonce,
+				gas_price: raw.gas_price.ok_or("missing gasPrice")?,
+				gas_limit: raw.gas,
+				action: to_action(raw.to),
+				value: raw.value,
+				input: raw.input,
+				signature: TransactionSignature::new(raw.v.as_u64(), r, s)
+					.ok_or("invalid signature")?,
+			}),
+			1 => TransactionV4::EIP2930(EIP2930Transaction {
+				chain_id: typed_chain_id?,
+				nonce: raw.nonce,
+				gas_price: raw.gas_price.ok_or("missing gasPrice")?,
+				gas_limit: raw.gas,
+				action: to_action(raw.to),
+				value: raw.value,
+				input: raw.input,
+				access_list,
+				odd_y_parity: odd_y_parity?,
+				r,
+				s,
+			}),
+			2 => TransactionV4::EIP1559(EIP1559Transaction {
+				chain_id: typed_chain_id?,
+				nonce: raw.nonce,
+				max_priority_fee_per_gas: max_priority_fee_per_gas?,
+				max_fee_per_gas: max_fee_per_gas?,
+				gas_limit: raw.gas,
+				action: to_action(raw.to),
+				value: raw.value,
+				input: raw.input,
+				access_list,
+				odd_y_parity: odd_y_parity?,
+				r,
+				s,
+			}),
+			3 => TransactionV4::EIP4844(EIP4844Transaction {
+				chain_id: typed_chain_id?,
+				nonce: raw.nonce,
+				max_priority_fee_per_gas: max_priority_fee_per_gas?,
+				max_fee_per_gas: max_fee_per_gas?,
+				gas_limit: raw.gas,
+				to: raw.to.ok_or("missing to")?,
+				value: raw.value,
+				input: raw.input,
+				access_list,
+				max_fee_per_blob_gas: raw.max_fee_per_blob_gas.ok_or("missing maxFeePerBlobGas")?,
+				blob_versioned_hashes: raw.blob_versioned_hashes.unwrap_or_default(),
+				odd_y_parity: odd_y_parity?,
+				r,
+				s,
+			}),
+			4 => TransactionV4::EIP7702(EIP7702Transaction {
+				chain_id: typed_chain_id?,
+				nonce: raw.nonce,
+				max_priority_fee_per_gas: max_priority_fee_per_gas?,
+				max_fee_per_gas: max_fee_per_gas?,
+				gas_limit: raw.gas,
+				to: raw.to.ok_or("missing to")?,
+				value: raw.value,
+				input: raw.input,
+				access_list,
+				authorization_list: raw
+					.authorization_list
+					.unwrap_or_default()
+					.into_iter()
+					.map(Authorization::try_from)
+					.collect::<Result<_, _>>()?,
+				odd_y_parity: odd_y_parity?,
+				r,
+				s,
+			}),
+			_ => return Err("unsupported transaction type"),
+		};
+
+		if transaction.hash() != raw.hash {
+			return Err("transaction hash mismatch");
+		}
+
+		Ok(Self {
+			hash: raw.hash,
+			from: raw.from,
+			block_hash: raw.block_hash,
+			block_number: raw.block_number,
+			transaction_index: raw.transaction_index,
+			transaction,
+		})
+	}
+}
+
+/// Log as returned by `eth_getLogs` and inside receipts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "RawLog", into = "RawLog")]
+pub struct RpcLog {
+	pub log: Log,
+	/// `None` while the log's transaction is pending, as are the other inclusion fields.
+	pub block_hash: Option<H256>,
+	pub block_number: Option<U256>,
+	pub transaction_hash: Option<H256>,
+	pub transaction_index: Option<U256>,
+	pub log_index: Option<U256>,
+	/// Whether the log was removed by a chain reorganization.
+	pub removed: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawLog {
+	address: Address,
+	topics: Vec<H256>,
+	#[serde(with = "crate::serde_helpers::bytes")]
+	data: Vec<u8>,
+	block_number: Option<U256>,
+	transaction_hash: Option<H256>,
+	transaction_index: Option<U256>,
+	block_hash: Option<H256>,
+	log_index: Option<U256>,
+	#[serde(default)]
+	removed: bool,
+}
+
+impl From<RpcLog> for RawLog {
+	fn from(log: RpcLog) -> Self {
+		Self {
+			address: log.log.address,
+			topics: log.log.topics,
+			data: log.log.data,
+			block_number: log.block_number,
+			transaction_hash: log.transaction_hash,
+			transaction_index: log.transaction_index,
+			block_hash: log.block_hash,
+			log_index: log.log_index,
+			removed: log.removed,
+		}
+	}
+}
+
+impl From<RawLog> for RpcLog {
+	fn from(raw: RawLog) -> Self {
+		Self {
+			log: Log {
+				address: raw.address,
+				topics: raw.topics,
+				data: raw.data,
+			},
+			block_hash: raw.block_hash,
+			block_number: raw.block_number,
+			transaction_hash: raw.transaction_hash,
+			transaction_index: raw.transaction_index,
+			log_index: raw.log_index,
+			removed: raw.removed,
+		}
+	}
+}
+
+/// Receipt as returned by `eth_getTransactionReceipt`.
+///
+/// The logs of `receipt` are written with the receipt's inclusion fields and consecutive log
+/// indices starting at `log_index`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawReceipt", into = "RawReceipt")]
+pub struct RpcReceipt {
+	pub transaction_hash: H256,
+	pub transaction_index: U256,
+	pub block_hash: H256,
+	pub block_number: U256,
+	pub from: Address,
+	pub to: Option<Address>,
+	pub contract_address: Option<Address>,
+	/// Gas used by this transaction alone.
+	pub gas_used: U256,
+	pub effective_gas_price: Option<U256>,
+	pub blob_gas_used: Option<U256>,
+	pub blob_gas_price: Option<U256>,
+	/// Index in the block of the receipt's first log.
+	pub log_index: U256,
+	pub receipt: ReceiptAny,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawReceipt {
+	#[serde(rename = "type", default)]
+	tx_type: U64,
+	transaction_hash: H256,
+	transaction_index: U256,
+	block_hash: H256,
+	block_number: U256,
+	from: Address,
+	to: Option<Address>,
+	contract_address: Option<Address>,
+	cumulative_gas_used: U256,
+	gas_used: U256,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	effective_gas_price: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	blob_gas_used: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	blob_gas_price: Option<U256>,
+	logs: Vec<RpcLog>,
+	logs_bloom: Bloom,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	root: Option<H256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	status: Option<U64>,
+}
+
+impl From<RpcReceipt> for RawReceipt {
+	fn from(receipt: RpcReceipt) -> Self {
+		let (tx_type, root, status, data) = match receipt.receipt {
+			ReceiptAny::Frontier(r) => {
+				let data = EIP658ReceiptData {
+					status_code: 0,
+					used_gas: r.used_gas,
+					logs_bloom: r.logs_bloom,
+					logs: r.logs,
+				};
+				(0, Some(r.state_root), None, data)
+			}
+			ReceiptAny::EIP658(r) => (0, None, Some(r.status_code.into()), r),
+			ReceiptAny::EIP2930(r) => (1, None, Some(r.status_code.into()), r),
+			ReceiptAny::EIP1559(r) => (2, None, Some(r.status_code.into()), r),
+			ReceiptAny::EIP4844(r) => (3, None, Some(r.status_code.into()), r),
+			ReceiptAny::EIP7702(r) => (4, None, Some(r.status_code.into()), r),
+		};
+
+		let (block_hash, block_number) = (receipt.block_hash, receipt.block_number);
+		let (transaction_hash, transaction_index) =
+			(receipt.transaction_hash, receipt.transaction_index);
+		let first_log_index = receipt.log_index;
+		let logs = data
+			.logs
+			.into_iter()
+			.enumerate()
+			.map(|(i, log)| RpcLog {
+				log,
+				block_hash: Some(block_hash),
+				block_number: Some(block_number),
+				transaction_hash: Some(transaction_hash),
+				transaction_index: Some(transaction_index),
+				log_index: Some(first_log_index + i),
+				removed: false,
+			})
+			.collect();
+
+		Self {
+			tx_type: tx_type.into(),
+			transaction_hash: receipt.transaction_hash,
+			transaction_index: receipt.transaction_index,
+			block_hash: receipt.block_hash,
+			block_number: receipt.block_number,
+			from: receipt.from,
+			to: receipt.to,
+			contract_address: receipt.contract_address,
+			cumulative_gas_used: data.used_gas,
+			gas_used: receipt.gas_used,
+			effective_gas_price: receipt.effective_gas_price,
+			blob_gas_used: receipt.blob_gas_used,
+			blob_gas_price: receipt.blob_gas_price,
+			logs,
+			logs_bloom: data.logs_bloom,
+			root,
+			status,
+		}
+	}
+}
+
+impl TryFrom<RawReceipt> for RpcReceipt {
+	type Error = &'static str;
+
+	fn try_from(raw: RawReceipt) -> Result<Self, Self::Error> {
+		let log_index = raw
+			.logs
+			.first()
+			.and_then(|log| log.log_index)
+			.unwrap_or_default();
+		let logs = raw.logs.into_iter().map(|log| log.log).collect();
+
+		let receipt = match (raw.root, raw.status) {
+			(Some(state_root), None) if raw.tx_type.is_zero() => {
+				ReceiptAny::Frontier(FrontierReceiptData {
+					state_root,
+					used_gas: raw.cumulative_gas_used,
+					logs_bloom: raw.logs_bloom,
+					logs,
+				})
+			}
+			(_, Some(status)) => {
+				let data = EIP658ReceiptData {
+					status_code: u8::try_from(status.as_u64()).map_err(|_| "invalid status")?,
+					used_gas: raw.cumulative_gas_used,
+					logs_bloom: raw.logs_bloom,
+					logs,
+				};
+				match raw.tx_type.as_u64() {
+					0 => ReceiptAny::EIP658(data),
+					1 => ReceiptAny::EIP2930(data),
+					2 => ReceiptAny::EIP1559(data),
+					3 => ReceiptAny::EIP4844(data),
+					4 => ReceiptAny::EIP7702(data),
+					_ => return Err("unsupported receipt type"),
+				}
+			}
+			_ => return Err("missing status"),
+		};
+
+		Ok(Self {
+			transaction_hash: raw.transaction_hash,
+			transaction_index: raw.transaction_index,
+			block_hash: raw.block_hash,
+			block_number: raw.block_number,
+			from: raw.from,
+			to: raw.to,
+			contract_address: raw.contract_address,
+			gas_used: raw.gas_used,
+			effective_gas_price: raw.effective_gas_price,
+			blob_gas_used: raw.blob_gas_used,
+			blob_gas_price: raw.blob_gas_price,
+			log_index,
+			receipt,
+		})
+	}
+}
+
+/// Transactions of an [`RpcBlock`], depending on whether full transactions were requested.
+///
+/// An empty list always deserializes as `Hashes`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RpcBlockTransactions {
+	Hashes(Vec<H256>),
+	Full(Vec<RpcTransaction>),
+}
+
+/// Block as returned by `eth_getBlockByNumber` and `eth_getBlockByHash`.
+///
+/// Ommers are only listed by hash. Deserialization checks `hash` against `header`, so pending
+/// blocks are rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawBlock", into = "RawBlock")]
+pub struct RpcBlock {
+	pub header: Header,
+	pub transactions: RpcBlockTransactions,
+	pub uncles: Vec<H256>,
+	pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawBlock {
+	hash: H256,
+	parent_hash: H256,
+	sha3_uncles: H256,
+	miner: Address,
+	state_root: H256,
+	transactions_root: H256,
+	receipts_root: H256,
+	logs_bloom: Bloom,
+	difficulty: U256,
+	number: U256,
+	gas_limit: U256,
+	gas_used: U256,
+	timestamp: U64,
+	#[serde(with = "crate::serde_helpers::bytes")]
+	extra_data: Vec<u8>,
+	mix_hash: H256,
+	nonce: H64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	base_fee_per_gas: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	withdrawals_root: Option<H256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	blob_gas_used: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	excess_blob_gas: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	parent_beacon_block_root: Option<H256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	requests_hash: Option<H256>,
+	transactions: RpcBlockTransactions,
+	uncles: Vec<H256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	withdrawals: Option<Vec<RawWithdrawal>>,
+}
+
+impl From<RpcBlock> for RawBlock {
+	fn from(block: RpcBlock) -> Self {
+		let header = block.header;
+		Self {
+			hash: header.hash(),
+			parent_hash: header.parent_hash,
+			sha3_uncles: header.ommers_hash,
+			miner: header.beneficiary,
+			state_root: header.state_root,
+			transactions_root: header.transactions_root,
+			receipts_root: header.receipts_root,
+			logs_bloom: header.logs_bloom,
+			difficulty: header.difficulty,
+			number: header.number,
+			gas_limit: header.gas_limit,
+			gas_used: header.gas_used,
+			timestamp: header.timestamp.into(),
+			extra_data: header.extra_data,
+			mix_hash: header.mix_hash,
+			nonce: header.nonce,
+			base_fee_per_gas: header.base_fee,
+			withdrawals_root: header.withdrawals_root,
+			blob_gas_used: header.blob_gas_used,
+			excess_blob_gas: header.excess_blob_gas,
+			parent_beacon_block_root: header.parent_beacon_block_root,
+			requests_hash: header.requests_hash,
+			transactions: block.transactions,
+			uncles: block.uncles,
+			withdrawals: block
+				.withdrawals
+				.map(|withdrawals| withdrawals.into_iter().map(Into::into).collect()),
+		}
+	}
+}
+
+impl TryFrom<RawBlock> for RpcBlock {
+	type Error = &'static str;
+
+	fn try_from(raw: RawBlock) -> Result<Self, Self::Error> {
+		let header = Header {
+			parent_hash: raw.parent_hash,
+			ommers_hash: raw.sha3_uncles,
+			beneficiary: raw.miner,
+			state_root: raw.state_root,
+			transactions_root: raw.transactions_root,
+			receipts_root: raw.receipts_root,
+			logs_bloom: raw.logs_bloom,
+			difficulty: raw.difficulty,
+			number: raw.number,
+			gas_limit: raw.gas_limit,
+			gas_used: raw.gas_used,
+			timestamp: raw.timestamp.as_u64(),
+			extra_data: raw.extra_data,
+			mix_hash: raw.mix_hash,
+			nonce: raw.nonce,
+			base_fee: raw.base_fee_per_gas,
+			withdrawals_root: raw.withdrawals_root,
+			blob_gas_used: raw.blob_gas_used,
+			excess_blob_gas: raw.excess_blob_gas,
+			parent_beacon_block_root: raw.parent_beacon_block_root,
+			requests_hash: raw.requests_hash,
+		};
+		if header.optional_field_gap().is_some() {
+			return Err("header optional fields have a gap");
+		}
+		if header.hash() != raw.hash {
+			return Err("block hash mismatch");
+		}
+
+		Ok(Self {
+			header,
+			transactions: raw.transactions,
+			uncles: raw.uncles,
+			withdrawals: raw
+				.withdrawals
+				.map(|withdrawals| withdrawals.into_iter().map(Into::into).collect()),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	// Signed example transaction from EIP-155.
+	fn eip155_json() -> serde_json::Value {
+		json!({
+			"blockHash": "0x1d59ff54b1eb26b013ce3cb5fc9dab3705b415a67127a003c3e61eb445bb8df2",
+			"blockNumber": "0x5daf3b",
+			"from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+			"gas": "0x5208",
+			"gasPrice": "0x4a817c800",
+			"hash": "0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788",
+			"input": "0x",
+			"nonce": "0x9",
+			"to": "0x3535353535353535353535353535353535353535",
+			"transactionIndex": "0x41",
+			"value": "0xde0b6b3a7640000",
+			"type": "0x0",
+			"chainId": "0x1",
+			"v": "0x25",
+			"r": "0x28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+			"s": "0x67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+		})
+	}
+
+	#[test]
+	fn round_trips_legacy_transaction() {
+		let json = eip155_json();
+		let tx: RpcTransaction = serde_json::from_value(json.clone()).unwrap();
+		match &tx.transaction {
+			TransactionV4::Legacy(t) => {
+				assert_eq!(t.nonce, 9.into());
+				assert_eq!(t.signature.chain_id(), Some(1));
+			}
+			_ => panic!("expected a legacy transaction"),
+		}
+		assert_eq!(tx.transaction_index, Some(0x41.into()));
+		assert_eq!(serde_json::to_value(&tx).unwrap(), json);
+
+		let mut tampered = json;
+		tampered["nonce"] = "0xa".into();
+		assert!(serde_json::from_value::<RpcTransaction>(tampered).is_err());
+	}
+
+	#[test]
+	fn round_trips_dynamic_fee_transaction() {
+		let tx = RpcTransaction::pending(
+			TransactionV4::EIP1559(EIP1559Transaction {
+				chain_id: 1,
+				nonce: 1.into(),
+				max_priority_fee_per_gas: 2.into(),
+				max_fee_per_gas: 3.into(),
+				gas_limit: 21_000.into(),
+				action: TransactionAction::Create,
+				value: 0.into(),
+				input: vec![0x60, 0x00],
+				access_list: vec![],
+				odd_y_parity: true,
+				r: H256::repeat_byte(1),
+				s: H256::from_low_u64_be(2),
+			}),
+			Address::repeat_byte(9),
+		);
+		let json = serde_json::to_value(&tx).unwrap();
+
+		assert_eq!(json["type"], "0x2");
+		assert_eq!(json["to"], serde_json::Value::Null);
+		assert_eq!(json["blockHash"], serde_json::Value::Null);
+		assert_eq!(json["v"], "0x1");
+		assert_eq!(json["yParity"], "0x1");
+		assert_eq!(json["s"], "0x2");
+		assert_eq!(json["accessList"], json!([]));
+		assert!(json.get("gasPrice").is_none());
+		assert_eq!(serde_json::from_value::<RpcTransaction>(json).unwrap(), tx);
+	}
+
+	#[test]
+	fn round_trips_receipts() {
+		let json = json!({
+			"type": "0x2",
+			"transactionHash": "0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788",
+			"transactionIndex": "0x41",
+			"blockHash": "0x1d59ff54b1eb26b013ce3cb5fc9dab3705b415a67127a003c3e61eb445bb8df2",
+			"blockNumber": "0x5daf3b",
+			"from": "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+			"to": "0x3535353535353535353535353535353535353535",
+			"contractAddress": null,
+			"cumulativeGasUsed": "0x1e8480",
+			"gasUsed": "0x5208",
+			"effectiveGasPrice": "0x4a817c800",
+			"logs": [{
+				"address": "0x3535353535353535353535353535353535353535",
+				"topics": ["0x0000000000000000000000000000000000000000000000000000000000000001"],
+				"data": "0x01",
+				"blockNumber": "0x5daf3b",
+				"transactionHash": "0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788",
+				"transactionIndex": "0x41",
+				"blockHash": "0x1d59ff54b1eb26b013ce3cb5fc9dab3705b415a67127a003c3e61eb445bb8df2",
+				"logIndex": "0x7",
+				"removed": false
+			}],
+			"logsBloom": format!("0x{}", "00".repeat(256)),
+			"status": "0x1"
+		});
+		let receipt: RpcReceipt = serde_json::from_value(json.clone()).unwrap();
+		match &receipt.receipt {
+			ReceiptAny::EIP1559(r) => {
+				assert_eq!(r.status_code, 1);
+				assert_eq!(r.used_gas, 2_000_000.into());
+				assert_eq!(r.logs[0].data, vec![1]);
+			}
+			_ => panic!("expected an EIP-1559 receipt"),
+		}
+		assert_eq!(receipt.log_index, 7.into());
+		assert_eq!(serde_json::to_value(&receipt).unwrap(), json);
+
+		let mut frontier = json;
+		frontier["type"] = "0x0".into();
+		frontier["root"] = format!("0x{}", "11".repeat(32)).into();
+		frontier.as_object_mut().unwrap().remove("status");
+		let receipt: RpcReceipt = serde_json::from_value(frontier).unwrap();
+		assert!(matches!(receipt.receipt, ReceiptAny::Frontier(_)));
+	}
+
+	#[test]
+	fn round_trips_blocks() {
+		let block = RpcBlock {
+			header: Header {
+				parent_hash: H256::repeat_byte(1),
+				ommers_hash: H256::repeat_byte(2),
+				beneficiary: Address::repeat_byte(3),
+				state_root: H256::repeat_byte(4),
+				transactions_root: H256::repeat_byte(5),
+				receipts_root: H256::repeat_byte(6),
+				logs_bloom: Bloom::zero(),
+				difficulty: 0.into(),
+				number: 0x1234.into(),
+				gas_limit: 30_000_000.into(),
+				gas_used: 21_000.into(),
+				timestamp: 1_700_000_000,
+				extra_data: vec![0xab],
+				mix_hash: H256::repeat_byte(7),
+				nonce: H64::zero(),
+				base_fee: Some(7.into()),
+				withdrawals_root: Some(H256::repeat_byte(8)),
+				blob_gas_used: None,
+				excess_blob_gas: None,
+				parent_beacon_block_root: None,
+				requests_hash: None,
+			},
+			transactions: RpcBlockTransactions::Full(vec![
+				serde_json::from_value(eip155_json()).unwrap()
+			]),
+			uncles: vec![],
+			withdrawals: Some(vec![]),
+		};
+		let json = serde_json::to_value(&block).unwrap();
+
+		assert_eq!(json["hash"], json!(block.header.hash()));
+		assert_eq!(json["miner"], json!(Address::repeat_byte(3)));
+		assert_eq!(json["number"], "0x1234");
+		assert_eq!(json["timestamp"], "0x6553f100");
+		assert_eq!(json["nonce"], "0x0000000000000000");
+		assert_eq!(json["baseFeePerGas"], "0x7");
+		assert!(json.get("blobGasUsed").is_none());
+		assert_eq!(
+			serde_json::from_value::<RpcBlock>(json.clone()).unwrap(),
+			block
+		);
+
+		let mut hashes = json;
+		hashes["transactions"] = json!([eip155_json()["hash"]]);
+		let decoded: RpcBlock = serde_json::from_value(hashes.clone()).unwrap();
+		assert!(matches!(
+			decoded.transactions,
+			RpcBlockTransactions::Hashes(_)
+		));
+
+		hashes["gasUsed"] = "0x0".into();
+		assert!(serde_json::from_value::<RpcBlock>(hashes).is_err());
+	}
+
+	/// Check a block in the shape a node returns it against its known hash.
+	fn decode_node_block(json: serde_json::Value) -> RpcBlock {
+		let block: RpcBlock = serde_json::from_value(json.clone()).unwrap();
+		assert_eq!(json!(block.header.hash()), json["hash"]);
+
+		let mut encoded = serde_json::to_value(&block).unwrap();
+		for (field, value) in json.as_object().unwrap() {
+			if field != "size" && field != "totalDifficulty" {
+				assert_eq!(
+					encoded.as_object_mut().unwrap().remove(field),
+					Some(value.clone())
+				);
+			}
+		}
+		assert_eq!(encoded, json!({}));
+		block
+	}
+
+	#[test]
+	fn decodes_post_london_node_block() {
+		// `eth_getBlockByNumber("0x0", false)` on Sepolia, where London is active from genesis.
+		let block = decode_node_block(json!({
+			"baseFeePerGas": "0x3b9aca00",
+			"difficulty": "0x20000",
+			"extraData": "0x5365706f6c69612c20417468656e732c204174746963612c2047726565636521",
+			"gasLimit": "0x1c9c380",
+			"gasUsed": "0x0",
+			"hash": "0x25a5cc106eea7138acab33231d7160d69cb777ee0c2c553fcddf5138993e6dd9",
+			"logsBloom": format!("0x{}", "00".repeat(256)),
+			"miner": "0x0000000000000000000000000000000000000000",
+			"mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+			"nonce": "0x0000000000000000",
+			"number": "0x0",
+			"parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+			"receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+			"sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+			"size": "0x225",
+			"stateRoot": "0x5eb6e371a698b8d68f665192350ffcecbbbf322916f4b51bd79bb6887da3f494",
+			"timestamp": "0x6159af19",
+			"totalDifficulty": "0x20000",
+			"transactions": [],
+			"transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+			"uncles": []
+		}));
+		assert_eq!(block.header.base_fee, Some(1_000_000_000.into()));
+		assert_eq!(block.withdrawals, None);
+	}
+
+	#[test]
+	fn decodes_post_cancun_node_block() {
+		// `eth_getBlockByNumber("0x0", false)` on Hoodi, where Cancun is active from genesis.
+		let block = decode_node_block(json!({
+			"baseFeePerGas": "0x3b9aca00",
+			"blobGasUsed": "0x0",
+			"difficulty": "0x1",
+			"excessBlobGas": "0x0",
+			"extraData": "0x",
+			"gasLimit": "0x2255100",
+			"gasUsed": "0x0",
+			"hash": "0xbbe312868b376a3001692a646dd2d7d1e4406380dfd86b98aa8a34d1557c971b",
+			"logsBloom": format!("0x{}", "00".repeat(256)),
+			"miner": "0x0000000000000000000000000000000000000000",
+			"mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+			"nonce": "0x0000000000001234",
+			"number": "0x0",
+			"parentBeaconBlockRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+			"parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+			"receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+			"sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+			"size": "0x247",
+			"stateRoot": "0xda87d7f5f91c51508791bbcbd4aa5baf04917830b86985eeb9ad3d5bfb657576",
+			"timestamp": "0x67d80ec0",
+			"transactions": [],
+			"transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+			"uncles": [],
+			"withdrawals": [],
+			"withdrawalsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+		}));
+		assert_eq!(block.header.blob_gas_used, Some(0.into()));
+		assert_eq!(block.header.parent_beacon_block_root, Some(H256::zero()));
+		assert_eq!(block.withdrawals, Some(vec![]));
+	}
+
+	#[test]
+	fn decodes_typed_receipt_into_its_consensus_encoding() {
+		// Receipt of a successful EIP-1559 transfer that is alone in its block; the receipts
+		// root of such a block is known. The transaction and block metadata are placeholders.
+		let receipt: RpcReceipt = serde_json::from_value(json!({
+			"blockHash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+			"blockNumber": "0x1",
+			"contractAddress": null,
+			"cumulativeGasUsed": "0x5208",
+			"effectiveGasPrice": "0x3b9aca07",
+			"from": "0x2222222222222222222222222222222222222222",
+			"gasUsed": "0x5208",
+			"logs": [],
+			"logsBloom": format!("0x{}", "00".repeat(256)),
+			"status": "0x1",
+			"to": "0x3333333333333333333333333333333333333333",
+			"transactionHash": "0x4444444444444444444444444444444444444444444444444444444444444444",
+			"transactionIndex": "0x0",
+			"type": "0x2"
+		}))
+		.unwrap();
+
+		assert!(matches!(receipt.receipt, ReceiptAny::EIP1559(_)));
+		assert_eq!(
+			crate::receipts_root(&[receipt.receipt]),
+			H256::from(hex_literal::hex!(
+				"f78dfb743fbd92ade140711c8bbc542b5e307f0ab7984eff35d751969fe57efa"
+			))
+		);
+	}
+}
@@ -0,0 +1,255 @@
+//! Serde helpers for the hex encodings used by JSON files and JSON-RPC.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+use ethereum_types::U256;
+#[cfg(feature = "arbitrary-precision")]
+use serde::de::{MapAccess, Unexpected};
+use serde::{
+	de::{Deserializer, Error, Visitor},
+	Deserialize, Serialize, Serializer,
+};
+
+fn parse_quantity<E: Error>(s: &str) -> Result<U256, E> {
+	match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+		Some("") => Ok(U256::zero()),
+		Some(hex) => U256::from_str_radix(hex, 16)
+			.map_err(|_| E::custom(format!("invalid hex quantity {s}"))),
+		None => {
+			U256::from_dec_str(s).map_err(|_| E::custom(format!("invalid decimal quantity {s}")))
+		}
+	}
+}
+
+/// Key under which serde_json's `arbitrary_precision` feature passes the digits of a number.
+#[cfg(feature = "arbitrary-precision")]
+const ARBITRARY_PRECISION_NUMBER: &str = "$serde_json::private::Number";
+
+/// Largest integer every smaller integer of which a float represents exactly.
+const MAX_EXACT_FLOAT: f64 = (1_u64 << 53) as f64;
+
+struct QuantityVisitor;
+
+impl<'de> Visitor<'de> for QuantityVisitor {
+	type Value = U256;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a number, or a hex or decimal string")
+	}
+
+	fn visit_u64<E: Error>(self, v: u64) -> Result<U256, E> {
+		Ok(v.into())
+	}
+
+	fn visit_u128<E: Error>(self, v: u128) -> Result<U256, E> {
+		Ok(v.into())
+	}
+
+	fn visit_f64<E: Error>(self, v: f64) -> Result<U256, E> {
+		// Above 2^53 the float may already have been rounded by the parser. Large integers
+		// such as `terminalTotalDifficulty` must then come as strings or, with serde_json,
+		// through the `arbitrary-precision` feature.
+		if v < 0.0 || v.fract() != 0.0 || v > MAX_EXACT_FLOAT {
+			return Err(E::custom(format!(
+				"quantity {v} cannot be read exactly; write it as a string or enable the \
+				 `arbitrary-precision` feature"
+			)));
+		}
+		Ok(U256::from(v as u64))
+	}
+
+	#[cfg(feature = "arbitrary-precision")]
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<U256, A::Error> {
+		match map.next_key::<String>()? {
+			Some(key) if key == ARBITRARY_PRECISION_NUMBER => {
+				let digits = map.next_value::<String>()?;
+				U256::from_dec_str(&digits)
+					.map_err(|_| A::Error::custom(format!("invalid decimal quantity {digits}")))
+			}
+			_ => Err(A::Error::invalid_type(Unexpected::Map, &self)),
+		}
+	}
+
+	fn visit_str<E: Error>(self, v: &str) -> Result<U256, E> {
+		parse_quantity(v)
+	}
+}
+
+/// Integers written as JSON numbers, `0x`-prefixed hex strings or decimal strings.
+pub(crate) mod quantity {
+	use super::*;
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+		deserializer.deserialize_any(QuantityVisitor)
+	}
+}
+
+/// [`quantity`] for `u64` fields, serialized as hex.
+pub(crate) mod quantity_u64 {
+	use super::*;
+
+	pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("{value:#x}"))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+		let value = quantity::deserialize(deserializer)?;
+		if value > U256::from(u64::MAX) {
+			return Err(D::Error::custom("quantity does not fit in 64 bits"));
+		}
+		Ok(value.as_u64())
+	}
+}
+
+/// Optional [`quantity`] fields.
+pub(crate) mod option_quantity {
+	use super::*;
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Option<U256>, D::Error> {
+		struct OptionVisitor;
+
+		impl<'de> Visitor<'de> for OptionVisitor {
+			type Value = Option<U256>;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("null or a quantity")
+			}
+
+			fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+				Ok(None)
+			}
+
+			fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+				Ok(None)
+			}
+
+			fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+				quantity::deserialize(d).map(Some)
+			}
+		}
+
+		deserializer.deserialize_option(OptionVisitor)
+	}
+}
+
+/// Byte strings written as `0x`-prefixed hex.
+pub(crate) mod bytes {
+	use super::*;
+
+	fn encode_hex(bytes: &[u8]) -> String {
+		let mut out = String::with_capacity(2 + bytes.len() * 2);
+		out.push_str("0x");
+		for byte in bytes {
+			out.push_str(&format!("{byte:02x}"));
+		}
+		out
+	}
+
+	pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&encode_hex(value))
+	}
+
+	pub(crate) fn decode_hex<E: Error>(s: &str) -> Result<Vec<u8>, E> {
+		let hex = s.strip_prefix("0x").unwrap_or(s);
+		if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+			return Err(E::custom(format!("invalid hex string {s}")));
+		}
+		if hex.len() & 1 != 0 {
+			return Err(E::custom(format!("odd-length hex string {s}")));
+		}
+		(0..hex.len())
+			.step_by(2)
+			.map(|i| {
+				u8::from_str_radix(&hex[i..i + 2], 16)
+					.map_err(|_| E::custom(format!("invalid hex string {s}")))
+			})
+			.collect()
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		struct BytesVisitor;
+
+		impl<'de> Visitor<'de> for BytesVisitor {
+			type Value = Vec<u8>;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("a hex string")
+			}
+
+			fn visit_str<E: Error>(self, v: &str) -> Result<Vec<u8>, E> {
+				decode_hex(v)
+			}
+		}
+
+		deserializer.deserialize_str(BytesVisitor)
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+struct HexBytes(#[serde(with = "bytes")] Vec<u8>);
+
+/// Lists of [`bytes`].
+pub(crate) mod bytes_list {
+	use super::*;
+
+	pub fn serialize<S: Serializer>(value: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(value.iter().map(|bytes| HexBytes(bytes.clone())))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Vec<Vec<u8>>, D::Error> {
+		Ok(Vec::<HexBytes>::deserialize(deserializer)?
+			.into_iter()
+			.map(|bytes| bytes.0)
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Deserialize)]
+	struct Quantity(#[serde(deserialize_with = "quantity::deserialize")] U256);
+
+	fn parse(json: &str) -> Result<U256, serde_json::Error> {
+		serde_json::from_str::<Quantity>(json).map(|q| q.0)
+	}
+
+	#[test]
+	fn reads_large_numbers_exactly() {
+		let ttd = U256::from_dec_str("58750000000000000000000").unwrap();
+		#[cfg(feature = "arbitrary-precision")]
+		assert_eq!(parse("58750000000000000000000").unwrap(), ttd);
+		#[cfg(not(feature = "arbitrary-precision"))]
+		assert!(parse("58750000000000000000000").is_err());
+		assert_eq!(parse("\"58750000000000000000000\"").unwrap(), ttd);
+		assert_eq!(parse("\"0xc70d808a128d7380000\"").unwrap(), ttd);
+		assert_eq!(parse("42").unwrap(), 42.into());
+		assert!(parse("1.5").is_err());
+		assert!(parse("-1").is_err());
+		assert_eq!(
+			visit_f64(9_007_199_254_740_992.0).unwrap(),
+			U256::from(1_u64 << 53)
+		);
+		assert!(visit_f64(5.875e22).is_err());
+	}
+
+	fn visit_f64(v: f64) -> Result<U256, serde_json::Error> {
+		QuantityVisitor.visit_f64(v)
+	}
+
+	#[test]
+	fn rejects_non_hex_digits() {
+		assert!(bytes::decode_hex::<serde_json::Error>("0xa\u{e9}b").is_err());
+		assert!(bytes::decode_hex::<serde_json::Error>("0x+f").is_err());
+		assert!(bytes::decode_hex::<serde_json::Error>("0x-f").is_err());
+		assert_eq!(
+			bytes::decode_hex::<serde_json::Error>("0x0aff").unwrap(),
+			vec![0x0a, 0xff]
+		);
+	}
+}
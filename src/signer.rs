@@ -0,0 +1,379 @@
+//! Transaction signing over the unsigned `*TransactionMessage` types.
+
+use crate::{
+	crypto::public_to_address, Authorization, AuthorizationMessage, EIP1559Transaction,
+	EIP1559TransactionMessage, EIP2930Transaction, EIP2930TransactionMessage, EIP4844Transaction,
+	EIP4844TransactionMessage, EIP7702Transaction, EIP7702TransactionMessage, LegacyTransaction,
+	LegacyTransactionMessage, TransactionSignature,
+};
+use ethereum_types::{Address, H256};
+use k256::ecdsa::SigningKey;
+
+/// Unsigned message that turns into a signed value once given a signature.
+pub trait SignableMessage {
+	/// Signed value produced from this message.
+	type Signed;
+
+	/// Hash that is signed.
+	fn hash(&self) -> H256;
+
+	/// Attach a `(odd_y_parity, r, s)` signature to the message.
+	fn into_signed(self, odd_y_parity: bool, r: H256, s: H256)
+		-> Result<Self::Signed, SignerError>;
+}
+
+impl SignableMessage for LegacyTransactionMessage {
+	type Signed = LegacyTransaction;
+
+	fn hash(&self) -> H256 {
+		LegacyTransactionMessage::hash(self)
+	}
+
+	fn into_signed(
+		self,
+		odd_y_parity: bool,
+		r: H256,
+		s: H256,
+	) -> Result<LegacyTransaction, SignerError> {
+		let v = match self.chain_id {
+			Some(chain_id) => chain_id
+				.checked_mul(2)
+				.and_then(|v| v.checked_add(35 + odd_y_parity as u64))
+				.ok_or(SignerError::ChainIdTooLarge)?,
+			None => 27 + odd_y_parity as u64,
+		};
+
+		Ok(LegacyTransaction {
+			nonce: self.nonce,
+			gas_price: self.gas_price,
+			gas_limit: self.gas_limit,
+			action: self.action,
+			value: self.value,
+			input: self.input,
+			signature: TransactionSignature::new(v, r, s).ok_or(SignerError::InvalidSignature)?,
+		})
+	}
+}
+
+impl SignableMessage for EIP2930TransactionMessage {
+	type Signed = EIP2930Transaction;
+
+	fn hash(&self) -> H256 {
+		EIP2930TransactionMessage::hash(self)
+	}
+
+	fn into_signed(
+		self,
+		odd_y_parity: bool,
+		r: H256,
+		s: H256,
+	) -> Result<EIP2930Transaction, SignerError> {
+		Ok(EIP2930Transaction {
+			chain_id: self.chain_id,
+			nonce: self.nonce,
+			gas_price: self.gas_price,
+			gas_limit: self.gas_limit,
+			action: self.action,
+			value: self.value,
+			input: self.input,
+			access_list: self.access_list,
+			odd_y_parity,
+			r,
+			s,
+		})
+	}
+}
+
+impl SignableMessage for EIP1559TransactionMessage {
+	type Signed = EIP1559Transaction;
+
+	fn hash(&self) -> H256 {
+		EIP1559TransactionMessage::hash(self)
+	}
+
+	fn into_signed(
+		self,
+		odd_y_parity: bool,
+		r: H256,
+		s: H256,
+	) -> Result<EIP1559Transaction, SignerError> {
+		Ok(EIP1559Transaction {
+			chain_id: self.chain_id,
+			nonce: self.nonce,
+			max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+			max_fee_per_gas: self.max_fee_per_gas,
+			gas_limit: self.gas_limit,
+			action: self.action,
+			value: self.value,
+			input: self.input,
+			access_list: self.access_list,
+			odd_y_parity,
+			r,
+			s,
+		})
+	}
+}
+
+impl SignableMessage for EIP4844TransactionMessage {
+	type Signed = EIP4844Transaction;
+
+	fn hash(&self) -> H256 {
+		EIP4844TransactionMessage::hash(self)
+	}
+
+	fn into_signed(
+		self,
+		odd_y_parity: bool,
+		r: H256,
+		s: H256,
+	) -> Result<EIP4844Transaction, SignerError> {
+		Ok(EIP4844Transaction {
+			chain_id: self.chain_id,
+			nonce: self.nonce,
+			max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+			max_fee_per_gas: self.max_fee_per_gas,
+			gas_limit: self.gas_limit,
+			to: self.to,
+			value: self.value,
+			input: self.input,
+			access_list: self.access_list,
+			max_fee_per_blob_gas: self.max_fee_per_blob_gas,
+			blob_versioned_hashes: self.blob_versioned_hashes,
+			odd_y_parity,
+			r,
+			s,
+		})
+	}
+}
+
+impl SignableMessage for EIP7702TransactionMessage {
+	type Signed = EIP7702Transaction;
+
+	fn hash(&self) -> H256 {
+		EIP7702TransactionMessage::hash(self)
+	}
+
+	fn into_signed(
+		self,
+		odd_y_parity: bool,
+		r: H256,
+		s: H256,
+	) -> Result<EIP7702Transaction, SignerError> {
+		Ok(EIP7702Transaction {
+			chain_id: self.chain_id,
+			nonce: self.nonce,
+			max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+			max_fee_per_gas: self.max_fee_per_gas,
+			gas_limit: self.gas_limit,
+			to: self.to,
+			value: self.value,
+			input: self.input,
+			access_list: self.access_list,
+			authorization_list: self.authorization_list,
+			odd_y_parity,
+			r,
+			s,
+		})
+	}
+}
+
+impl SignableMessage for AuthorizationMessage {
+	type Signed = Authorization;
+
+	fn hash(&self) -> H256 {
+		AuthorizationMessage::hash(self)
+	}
+
+	fn into_signed(
+		self,
+		odd_y_parity: bool,
+		r: H256,
+		s: H256,
+	) -> Result<Authorization, SignerError> {
+		Ok(Authorization {
+			chain_id: self.chain_id,
+			address: self.address,
+			nonce: self.nonce,
+			y_parity: odd_y_parity as u8,
+			r,
+			s,
+		})
+	}
+}
+
+/// Signs message hashes on behalf of a single account.
+pub trait Signer {
+	/// Signing error.
+	type Error: From<SignerError>;
+
+	/// Address of the signing account.
+	fn address(&self) -> Address;
+
+	/// Sign a 32-byte hash, returning a low-s `(odd_y_parity, r, s)` signature.
+	fn sign_hash(&self, hash: H256) -> Result<(bool, H256, H256), Self::Error>;
+
+	/// Sign an unsigned message and return the matching signed value.
+	fn sign<M: SignableMessage>(&self, message: M) -> Result<M::Signed, Self::Error> {
+		let (odd_y_parity, r, s) = self.sign_hash(message.hash())?;
+		Ok(message.into_signed(odd_y_parity, r, s)?)
+	}
+}
+
+/// Error returned by [`LocalSigner`] and [`SignableMessage::into_signed`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignerError {
+	/// The secret key is zero or not below the curve order.
+	InvalidSecretKey,
+	/// The underlying ECDSA implementation failed to produce a signature.
+	SigningFailed,
+	/// The EIP-155 `v` of the message's chain id does not fit in 64 bits.
+	ChainIdTooLarge,
+	/// `r` or `s` is zero or not below the curve order.
+	InvalidSignature,
+}
+
+/// In-memory secp256k1 private key.
+#[derive(Clone)]
+pub struct LocalSigner {
+	key: SigningKey,
+	address: Address,
+}
+
+impl LocalSigner {
+	/// Create a signer from a raw 32-byte private key.
+	pub fn new(secret: H256) -> Result<Self, SignerError> {
+		let key =
+			SigningKey::from_slice(secret.as_bytes()).map_err(|_| SignerError::InvalidSecretKey)?;
+		let address = public_to_address(key.verifying_key());
+
+		Ok(Self { key, address })
+	}
+}
+
+impl core::fmt::Debug for LocalSigner {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("LocalSigner")
+			.field("address", &self.address)
+			.finish_non_exhaustive()
+	}
+}
+
+impl Signer for LocalSigner {
+	type Error = SignerError;
+
+	fn address(&self) -> Address {
+		self.address
+	}
+
+	fn sign_hash(&self, hash: H256) -> Result<(bool, H256, H256), SignerError> {
+		let (mut signature, recovery_id) = self
+			.key
+			.sign_prehash_recoverable(hash.as_bytes())
+			.map_err(|_| SignerError::SigningFailed)?;
+		let mut odd_y_parity = recovery_id.is_y_odd();
+		if let Some(normalized) = signature.normalize_s() {
+			signature = normalized;
+			odd_y_parity = !odd_y_parity;
+		}
+
+		let (r, s) = signature.split_bytes();
+		Ok((
+			odd_y_parity,
+			H256::from_slice(&r[..]),
+			H256::from_slice(&s[..]),
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{EnvelopedEncodable, TransactionAction};
+	use hex_literal::hex;
+
+	fn signer() -> LocalSigner {
+		LocalSigner::new(H256::repeat_byte(0x46)).unwrap()
+	}
+
+	#[test]
+	fn signs_eip155_legacy_transaction() {
+		let message = LegacyTransactionMessage {
+			nonce: 9.into(),
+			gas_price: 20_000_000_000_u64.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Call(
+				hex!("3535353535353535353535353535353535353535").into(),
+			),
+			value: 1_000_000_000_000_000_000_u64.into(),
+			input: vec![],
+			chain_id: Some(1),
+		};
+
+		let tx = signer().sign(message).unwrap();
+		assert_eq!(tx.signature.v(), 37);
+		assert!(tx.signature.is_low_s());
+		assert_eq!(
+			&tx.encode()[..],
+			&hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83")[..]
+		);
+	}
+
+	#[test]
+	fn rejects_unrepresentable_signatures() {
+		let message = LegacyTransactionMessage {
+			nonce: 0.into(),
+			gas_price: 1.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Create,
+			value: 0.into(),
+			input: vec![],
+			chain_id: Some(u64::MAX / 2),
+		};
+		assert_eq!(
+			signer().sign(message.clone()),
+			Err(SignerError::ChainIdTooLarge)
+		);
+		assert_eq!(
+			message.into_signed(false, H256::zero(), H256::repeat_byte(1)),
+			Err(SignerError::ChainIdTooLarge)
+		);
+
+		let message = LegacyTransactionMessage {
+			chain_id: Some((u64::MAX - 36) / 2),
+			nonce: 0.into(),
+			gas_price: 1.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Create,
+			value: 0.into(),
+			input: vec![],
+		};
+		assert!(signer().sign(message.clone()).is_ok());
+		assert_eq!(
+			message.into_signed(false, H256::zero(), H256::repeat_byte(1)),
+			Err(SignerError::InvalidSignature)
+		);
+	}
+
+	#[test]
+	fn signs_typed_transaction() {
+		let signer = signer();
+		let message = EIP1559TransactionMessage {
+			chain_id: 1,
+			nonce: 0.into(),
+			max_priority_fee_per_gas: 1_000_000_000_u64.into(),
+			max_fee_per_gas: 30_000_000_000_u64.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Call(
+				hex!("3535353535353535353535353535353535353535").into(),
+			),
+			value: 1.into(),
+			input: vec![],
+			access_list: vec![],
+		};
+
+		let tx = signer.sign(message).unwrap();
+		let signature = TransactionSignature::new(27 + tx.odd_y_parity as u64, tx.r, tx.s).unwrap();
+		assert!(signature.is_low_s());
+		assert_eq!(tx.recover_signer().unwrap(), signer.address());
+	}
+}
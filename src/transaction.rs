@@ -1,4 +1,4 @@
-use crate::{Bytes, EnvelopedDecodable, EnvelopedDecoderError, EnvelopedEncodable};
+use crate::{Bytes, DecodeError, EnvelopedDecodable, EnvelopedDecoderError, EnvelopedEncodable};
 use alloc::vec::Vec;
 use bytes::BytesMut;
 use core::ops::Deref;
@@ -202,6 +202,101 @@ impl Decodable for AccessListItem {
 
 pub type AccessList = Vec<AccessListItem>;
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(
+	feature = "with-serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "camelCase")
+)]
+pub struct Authorization {
+	pub chain_id: U256,
+	pub address: Address,
+	pub nonce: u64,
+	pub y_parity: u8,
+	pub r: H256,
+	pub s: H256,
+}
+
+impl Encodable for Authorization {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(6);
+		s.append(&self.chain_id);
+		s.append(&self.address);
+		s.append(&self.nonce);
+		s.append(&self.y_parity);
+		s.append(&U256::from_big_endian(&self.r[..]));
+		s.append(&U256::from_big_endian(&self.s[..]));
+	}
+}
+
+impl Decodable for Authorization {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 6 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		Ok(Self {
+			chain_id: rlp.val_at(0)?,
+			address: rlp.val_at(1)?,
+			nonce: rlp.val_at(2)?,
+			y_parity: rlp.val_at(3)?,
+			r: {
+				let mut rarr = [0_u8; 32];
+				rlp.val_at::<U256>(4)?.to_big_endian(&mut rarr);
+				H256::from(rarr)
+			},
+			s: {
+				let mut sarr = [0_u8; 32];
+				rlp.val_at::<U256>(5)?.to_big_endian(&mut sarr);
+				H256::from(sarr)
+			},
+		})
+	}
+}
+
+pub type AuthorizationList = Vec<Authorization>;
+
+/// Message signed by the authority of an EIP-7702 authorization tuple.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthorizationMessage {
+	pub chain_id: U256,
+	pub address: Address,
+	pub nonce: u64,
+}
+
+impl From<Authorization> for AuthorizationMessage {
+	fn from(a: Authorization) -> Self {
+		Self {
+			chain_id: a.chain_id,
+			address: a.address,
+			nonce: a.nonce,
+		}
+	}
+}
+
+impl Encodable for AuthorizationMessage {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(3);
+		s.append(&self.chain_id);
+		s.append(&self.address);
+		s.append(&self.nonce);
+	}
+}
+
+impl AuthorizationMessage {
+	pub fn hash(&self) -> H256 {
+		let encoded = rlp::encode(self);
+		let mut out = alloc::vec![0; 1 + encoded.len()];
+		out[0] = 5;
+		out[1..].copy_from_slice(&encoded);
+		H256::from_slice(Keccak256::digest(&out).as_slice())
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
 	feature = "with-codec",
@@ -219,7 +314,7 @@ pub struct LegacyTransactionMessage {
 
 impl LegacyTransactionMessage {
 	pub fn hash(&self) -> H256 {
-		H256::from_slice(Keccak256::digest(&rlp::encode(self)).as_slice())
+		H256::from_slice(Keccak256::digest(rlp::encode(self)).as_slice())
 	}
 }
 
@@ -367,6 +462,123 @@ impl EIP1559TransactionMessage {
 	}
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EIP4844TransactionMessage {
+	pub chain_id: u64,
+	pub nonce: U256,
+	pub max_priority_fee_per_gas: U256,
+	pub max_fee_per_gas: U256,
+	pub gas_limit: U256,
+	pub to: Address,
+	pub value: U256,
+	pub input: Bytes,
+	pub access_list: Vec<AccessListItem>,
+	pub max_fee_per_blob_gas: U256,
+	pub blob_versioned_hashes: Vec<H256>,
+}
+
+impl From<EIP4844Transaction> for EIP4844TransactionMessage {
+	fn from(t: EIP4844Transaction) -> Self {
+		Self {
+			chain_id: t.chain_id,
+			nonce: t.nonce,
+			max_priority_fee_per_gas: t.max_priority_fee_per_gas,
+			max_fee_per_gas: t.max_fee_per_gas,
+			gas_limit: t.gas_limit,
+			to: t.to,
+			value: t.value,
+			input: t.input,
+			access_list: t.access_list,
+			max_fee_per_blob_gas: t.max_fee_per_blob_gas,
+			blob_versioned_hashes: t.blob_versioned_hashes,
+		}
+	}
+}
+
+impl Encodable for EIP4844TransactionMessage {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(11);
+		s.append(&self.chain_id);
+		s.append(&self.nonce);
+		s.append(&self.max_priority_fee_per_gas);
+		s.append(&self.max_fee_per_gas);
+		s.append(&self.gas_limit);
+		s.append(&self.to);
+		s.append(&self.value);
+		s.append(&self.input);
+		s.append_list(&self.access_list);
+		s.append(&self.max_fee_per_blob_gas);
+		s.append_list(&self.blob_versioned_hashes);
+	}
+}
+
+impl EIP4844TransactionMessage {
+	pub fn hash(&self) -> H256 {
+		let encoded = rlp::encode(self);
+		let mut out = alloc::vec![0; 1 + encoded.len()];
+		out[0] = 3;
+		out[1..].copy_from_slice(&encoded);
+		H256::from_slice(Keccak256::digest(&out).as_slice())
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EIP7702TransactionMessage {
+	pub chain_id: u64,
+	pub nonce: U256,
+	pub max_priority_fee_per_gas: U256,
+	pub max_fee_per_gas: U256,
+	pub gas_limit: U256,
+	pub to: Address,
+	pub value: U256,
+	pub input: Bytes,
+	pub access_list: Vec<AccessListItem>,
+	pub authorization_list: Vec<Authorization>,
+}
+
+impl From<EIP7702Transaction> for EIP7702TransactionMessage {
+	fn from(t: EIP7702Transaction) -> Self {
+		Self {
+			chain_id: t.chain_id,
+			nonce: t.nonce,
+			max_priority_fee_per_gas: t.max_priority_fee_per_gas,
+			max_fee_per_gas: t.max_fee_per_gas,
+			gas_limit: t.gas_limit,
+			to: t.to,
+			value: t.value,
+			input: t.input,
+			access_list: t.access_list,
+			authorization_list: t.authorization_list,
+		}
+	}
+}
+
+impl Encodable for EIP7702TransactionMessage {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(10);
+		s.append(&self.chain_id);
+		s.append(&self.nonce);
+		s.append(&self.max_priority_fee_per_gas);
+		s.append(&self.max_fee_per_gas);
+		s.append(&self.gas_limit);
+		s.append(&self.to);
+		s.append(&self.value);
+		s.append(&self.input);
+		s.append_list(&self.access_list);
+		s.append_list(&self.authorization_list);
+	}
+}
+
+impl EIP7702TransactionMessage {
+	pub fn hash(&self) -> H256 {
+		let encoded = rlp::encode(self);
+		let mut out = alloc::vec![0; 1 + encoded.len()];
+		out[0] = 4;
+		out[1..].copy_from_slice(&encoded);
+		H256::from_slice(Keccak256::digest(&out).as_slice())
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
 	feature = "with-codec",
@@ -385,7 +597,7 @@ pub struct LegacyTransaction {
 
 impl LegacyTransaction {
 	pub fn hash(&self) -> H256 {
-		H256::from_slice(Keccak256::digest(&rlp::encode(self)).as_slice())
+		H256::from_slice(Keccak256::digest(rlp::encode(self)).as_slice())
 	}
 }
 
@@ -404,195 +616,439 @@ impl Encodable for LegacyTransaction {
 	}
 }
 
-impl Decodable for LegacyTransaction {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+impl LegacyTransaction {
+	/// Decode the RLP payload, reporting which field failed to decode.
+	pub fn decode_rlp(rlp: &Rlp) -> Result<Self, DecodeError> {
 		if rlp.item_count()? != 9 {
-			return Err(DecoderError::RlpIncorrectListLen);
+			return Err(DecoderError::RlpIncorrectListLen.into());
 		}
 
-		let v = rlp.val_at(6)?;
+		let v = rlp.val_at(6).map_err(DecodeError::field("v"))?;
 		let r = {
 			let mut rarr = [0_u8; 32];
-			rlp.val_at::<U256>(7)?.to_big_endian(&mut rarr);
+			rlp.val_at::<U256>(7)
+				.map_err(DecodeError::field("r"))?
+				.to_big_endian(&mut rarr);
 			H256::from(rarr)
 		};
 		let s = {
 			let mut sarr = [0_u8; 32];
-			rlp.val_at::<U256>(8)?.to_big_endian(&mut sarr);
+			rlp.val_at::<U256>(8)
+				.map_err(DecodeError::field("s"))?
+				.to_big_endian(&mut sarr);
 			H256::from(sarr)
 		};
-		let signature = TransactionSignature::new(v, r, s)
-			.ok_or(DecoderError::Custom("Invalid transaction signature format"))?;
+		let signature = TransactionSignature::new(v, r, s).ok_or(DecodeError::Field {
+			field: "signature",
+			error: DecoderError::Custom("Invalid transaction signature format"),
+		})?;
 
 		Ok(Self {
-			nonce: rlp.val_at(0)?,
-			gas_price: rlp.val_at(1)?,
-			gas_limit: rlp.val_at(2)?,
-			action: rlp.val_at(3)?,
-			value: rlp.val_at(4)?,
-			input: rlp.val_at(5)?,
+			nonce: rlp.val_at(0).map_err(DecodeError::field("nonce"))?,
+			gas_price: rlp.val_at(1).map_err(DecodeError::field("gas_price"))?,
+			gas_limit: rlp.val_at(2).map_err(DecodeError::field("gas_limit"))?,
+			action: rlp.val_at(3).map_err(DecodeError::field("action"))?,
+			value: rlp.val_at(4).map_err(DecodeError::field("value"))?,
+			input: rlp.val_at(5).map_err(DecodeError::field("input"))?,
 			signature,
 		})
 	}
 }
 
+impl Decodable for LegacyTransaction {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Self::decode_rlp(rlp).map_err(Into::into)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EIP2930Transaction {
+	pub chain_id: u64,
+	pub nonce: U256,
+	pub gas_price: U256,
+	pub gas_limit: U256,
+	pub action: TransactionAction,
+	pub value: U256,
+	pub input: Bytes,
+	pub access_list: AccessList,
+	pub odd_y_parity: bool,
+	pub r: H256,
+	pub s: H256,
+}
+
+impl EIP2930Transaction {
+	pub fn hash(&self) -> H256 {
+		let encoded = rlp::encode(self);
+		let mut out = alloc::vec![0; 1 + encoded.len()];
+		out[0] = 1;
+		out[1..].copy_from_slice(&encoded);
+		H256::from_slice(Keccak256::digest(&out).as_slice())
+	}
+}
+
+impl Encodable for EIP2930Transaction {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(11);
+		s.append(&self.chain_id);
+		s.append(&self.nonce);
+		s.append(&self.gas_price);
+		s.append(&self.gas_limit);
+		s.append(&self.action);
+		s.append(&self.value);
+		s.append(&self.input);
+		s.append_list(&self.access_list);
+		s.append(&self.odd_y_parity);
+		s.append(&U256::from_big_endian(&self.r[..]));
+		s.append(&U256::from_big_endian(&self.s[..]));
+	}
+}
+
+impl EIP2930Transaction {
+	/// Decode the RLP payload, reporting which field failed to decode.
+	pub fn decode_rlp(rlp: &Rlp) -> Result<Self, DecodeError> {
+		if rlp.item_count()? != 11 {
+			return Err(DecoderError::RlpIncorrectListLen.into());
+		}
+
+		Ok(Self {
+			chain_id: rlp.val_at(0).map_err(DecodeError::field("chain_id"))?,
+			nonce: rlp.val_at(1).map_err(DecodeError::field("nonce"))?,
+			gas_price: rlp.val_at(2).map_err(DecodeError::field("gas_price"))?,
+			gas_limit: rlp.val_at(3).map_err(DecodeError::field("gas_limit"))?,
+			action: rlp.val_at(4).map_err(DecodeError::field("action"))?,
+			value: rlp.val_at(5).map_err(DecodeError::field("value"))?,
+			input: rlp.val_at(6).map_err(DecodeError::field("input"))?,
+			access_list: rlp.list_at(7).map_err(DecodeError::field("access_list"))?,
+			odd_y_parity: rlp.val_at(8).map_err(DecodeError::field("odd_y_parity"))?,
+			r: {
+				let mut rarr = [0_u8; 32];
+				rlp.val_at::<U256>(9)
+					.map_err(DecodeError::field("r"))?
+					.to_big_endian(&mut rarr);
+				H256::from(rarr)
+			},
+			s: {
+				let mut sarr = [0_u8; 32];
+				rlp.val_at::<U256>(10)
+					.map_err(DecodeError::field("s"))?
+					.to_big_endian(&mut sarr);
+				H256::from(sarr)
+			},
+		})
+	}
+}
+
+impl Decodable for EIP2930Transaction {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Self::decode_rlp(rlp).map_err(Into::into)
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EIP1559Transaction {
+	pub chain_id: u64,
+	pub nonce: U256,
+	pub max_priority_fee_per_gas: U256,
+	pub max_fee_per_gas: U256,
+	pub gas_limit: U256,
+	pub action: TransactionAction,
+	pub value: U256,
+	pub input: Bytes,
+	pub access_list: AccessList,
+	pub odd_y_parity: bool,
+	pub r: H256,
+	pub s: H256,
+}
+
+impl EIP1559Transaction {
+	pub fn hash(&self) -> H256 {
+		let encoded = rlp::encode(self);
+		let mut out = alloc::vec![0; 1 + encoded.len()];
+		out[0] = 2;
+		out[1..].copy_from_slice(&encoded);
+		H256::from_slice(Keccak256::digest(&out).as_slice())
+	}
+}
+
+impl Encodable for EIP1559Transaction {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(12);
+		s.append(&self.chain_id);
+		s.append(&self.nonce);
+		s.append(&self.max_priority_fee_per_gas);
+		s.append(&self.max_fee_per_gas);
+		s.append(&self.gas_limit);
+		s.append(&self.action);
+		s.append(&self.value);
+		s.append(&self.input);
+		s.append_list(&self.access_list);
+		s.append(&self.odd_y_parity);
+		s.append(&U256::from_big_endian(&self.r[..]));
+		s.append(&U256::from_big_endian(&self.s[..]));
+	}
+}
+
+impl EIP1559Transaction {
+	/// Decode the RLP payload, reporting which field failed to decode.
+	pub fn decode_rlp(rlp: &Rlp) -> Result<Self, DecodeError> {
+		if rlp.item_count()? != 12 {
+			return Err(DecoderError::RlpIncorrectListLen.into());
+		}
+
+		Ok(Self {
+			chain_id: rlp.val_at(0).map_err(DecodeError::field("chain_id"))?,
+			nonce: rlp.val_at(1).map_err(DecodeError::field("nonce"))?,
+			max_priority_fee_per_gas: rlp
+				.val_at(2)
+				.map_err(DecodeError::field("max_priority_fee_per_gas"))?,
+			max_fee_per_gas: rlp
+				.val_at(3)
+				.map_err(DecodeError::field("max_fee_per_gas"))?,
+			gas_limit: rlp.val_at(4).map_err(DecodeError::field("gas_limit"))?,
+			action: rlp.val_at(5).map_err(DecodeError::field("action"))?,
+			value: rlp.val_at(6).map_err(DecodeError::field("value"))?,
+			input: rlp.val_at(7).map_err(DecodeError::field("input"))?,
+			access_list: rlp.list_at(8).map_err(DecodeError::field("access_list"))?,
+			odd_y_parity: rlp.val_at(9).map_err(DecodeError::field("odd_y_parity"))?,
+			r: {
+				let mut rarr = [0_u8; 32];
+				rlp.val_at::<U256>(10)
+					.map_err(DecodeError::field("r"))?
+					.to_big_endian(&mut rarr);
+				H256::from(rarr)
+			},
+			s: {
+				let mut sarr = [0_u8; 32];
+				rlp.val_at::<U256>(11)
+					.map_err(DecodeError::field("s"))?
+					.to_big_endian(&mut sarr);
+				H256::from(sarr)
+			},
+		})
+	}
+}
+
+impl Decodable for EIP1559Transaction {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Self::decode_rlp(rlp).map_err(Into::into)
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
 	feature = "with-codec",
 	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
 )]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct EIP2930Transaction {
+pub struct EIP4844Transaction {
 	pub chain_id: u64,
 	pub nonce: U256,
-	pub gas_price: U256,
+	pub max_priority_fee_per_gas: U256,
+	pub max_fee_per_gas: U256,
 	pub gas_limit: U256,
-	pub action: TransactionAction,
+	pub to: Address,
 	pub value: U256,
 	pub input: Bytes,
 	pub access_list: AccessList,
+	pub max_fee_per_blob_gas: U256,
+	pub blob_versioned_hashes: Vec<H256>,
 	pub odd_y_parity: bool,
 	pub r: H256,
 	pub s: H256,
 }
 
-impl EIP2930Transaction {
+impl EIP4844Transaction {
 	pub fn hash(&self) -> H256 {
 		let encoded = rlp::encode(self);
 		let mut out = alloc::vec![0; 1 + encoded.len()];
-		out[0] = 1;
+		out[0] = 3;
 		out[1..].copy_from_slice(&encoded);
 		H256::from_slice(Keccak256::digest(&out).as_slice())
 	}
 }
 
-impl Encodable for EIP2930Transaction {
+impl Encodable for EIP4844Transaction {
 	fn rlp_append(&self, s: &mut RlpStream) {
-		s.begin_list(11);
+		s.begin_list(14);
 		s.append(&self.chain_id);
 		s.append(&self.nonce);
-		s.append(&self.gas_price);
+		s.append(&self.max_priority_fee_per_gas);
+		s.append(&self.max_fee_per_gas);
 		s.append(&self.gas_limit);
-		s.append(&self.action);
+		s.append(&self.to);
 		s.append(&self.value);
 		s.append(&self.input);
 		s.append_list(&self.access_list);
+		s.append(&self.max_fee_per_blob_gas);
+		s.append_list(&self.blob_versioned_hashes);
 		s.append(&self.odd_y_parity);
 		s.append(&U256::from_big_endian(&self.r[..]));
 		s.append(&U256::from_big_endian(&self.s[..]));
 	}
 }
 
-impl Decodable for EIP2930Transaction {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-		if rlp.item_count()? != 11 {
-			return Err(DecoderError::RlpIncorrectListLen);
+impl EIP4844Transaction {
+	/// Decode the RLP payload, reporting which field failed to decode.
+	pub fn decode_rlp(rlp: &Rlp) -> Result<Self, DecodeError> {
+		if rlp.item_count()? != 14 {
+			return Err(DecoderError::RlpIncorrectListLen.into());
 		}
 
 		Ok(Self {
-			chain_id: rlp.val_at(0)?,
-			nonce: rlp.val_at(1)?,
-			gas_price: rlp.val_at(2)?,
-			gas_limit: rlp.val_at(3)?,
-			action: rlp.val_at(4)?,
-			value: rlp.val_at(5)?,
-			input: rlp.val_at(6)?,
-			access_list: rlp.list_at(7)?,
-			odd_y_parity: rlp.val_at(8)?,
+			chain_id: rlp.val_at(0).map_err(DecodeError::field("chain_id"))?,
+			nonce: rlp.val_at(1).map_err(DecodeError::field("nonce"))?,
+			max_priority_fee_per_gas: rlp
+				.val_at(2)
+				.map_err(DecodeError::field("max_priority_fee_per_gas"))?,
+			max_fee_per_gas: rlp
+				.val_at(3)
+				.map_err(DecodeError::field("max_fee_per_gas"))?,
+			gas_limit: rlp.val_at(4).map_err(DecodeError::field("gas_limit"))?,
+			to: rlp.val_at(5).map_err(DecodeError::field("to"))?,
+			value: rlp.val_at(6).map_err(DecodeError::field("value"))?,
+			input: rlp.val_at(7).map_err(DecodeError::field("input"))?,
+			access_list: rlp.list_at(8).map_err(DecodeError::field("access_list"))?,
+			max_fee_per_blob_gas: rlp
+				.val_at(9)
+				.map_err(DecodeError::field("max_fee_per_blob_gas"))?,
+			blob_versioned_hashes: rlp
+				.list_at(10)
+				.map_err(DecodeError::field("blob_versioned_hashes"))?,
+			odd_y_parity: rlp.val_at(11).map_err(DecodeError::field("odd_y_parity"))?,
 			r: {
 				let mut rarr = [0_u8; 32];
-				rlp.val_at::<U256>(9)?.to_big_endian(&mut rarr);
+				rlp.val_at::<U256>(12)
+					.map_err(DecodeError::field("r"))?
+					.to_big_endian(&mut rarr);
 				H256::from(rarr)
 			},
 			s: {
 				let mut sarr = [0_u8; 32];
-				rlp.val_at::<U256>(10)?.to_big_endian(&mut sarr);
+				rlp.val_at::<U256>(13)
+					.map_err(DecodeError::field("s"))?
+					.to_big_endian(&mut sarr);
 				H256::from(sarr)
 			},
 		})
 	}
 }
 
+impl Decodable for EIP4844Transaction {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Self::decode_rlp(rlp).map_err(Into::into)
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(
 	feature = "with-codec",
 	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
 )]
 #[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct EIP1559Transaction {
+pub struct EIP7702Transaction {
 	pub chain_id: u64,
 	pub nonce: U256,
 	pub max_priority_fee_per_gas: U256,
 	pub max_fee_per_gas: U256,
 	pub gas_limit: U256,
-	pub action: TransactionAction,
+	pub to: Address,
 	pub value: U256,
 	pub input: Bytes,
 	pub access_list: AccessList,
+	pub authorization_list: AuthorizationList,
 	pub odd_y_parity: bool,
 	pub r: H256,
 	pub s: H256,
 }
 
-impl EIP1559Transaction {
+impl EIP7702Transaction {
 	pub fn hash(&self) -> H256 {
 		let encoded = rlp::encode(self);
 		let mut out = alloc::vec![0; 1 + encoded.len()];
-		out[0] = 2;
+		out[0] = 4;
 		out[1..].copy_from_slice(&encoded);
 		H256::from_slice(Keccak256::digest(&out).as_slice())
 	}
 }
 
-impl Encodable for EIP1559Transaction {
+impl Encodable for EIP7702Transaction {
 	fn rlp_append(&self, s: &mut RlpStream) {
-		s.begin_list(12);
+		s.begin_list(13);
 		s.append(&self.chain_id);
 		s.append(&self.nonce);
 		s.append(&self.max_priority_fee_per_gas);
 		s.append(&self.max_fee_per_gas);
 		s.append(&self.gas_limit);
-		s.append(&self.action);
+		s.append(&self.to);
 		s.append(&self.value);
 		s.append(&self.input);
 		s.append_list(&self.access_list);
+		s.append_list(&self.authorization_list);
 		s.append(&self.odd_y_parity);
 		s.append(&U256::from_big_endian(&self.r[..]));
 		s.append(&U256::from_big_endian(&self.s[..]));
 	}
 }
 
-impl Decodable for EIP1559Transaction {
-	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-		if rlp.item_count()? != 12 {
-			return Err(DecoderError::RlpIncorrectListLen);
+impl EIP7702Transaction {
+	/// Decode the RLP payload, reporting which field failed to decode.
+	pub fn decode_rlp(rlp: &Rlp) -> Result<Self, DecodeError> {
+		if rlp.item_count()? != 13 {
+			return Err(DecoderError::RlpIncorrectListLen.into());
 		}
 
 		Ok(Self {
-			chain_id: rlp.val_at(0)?,
-			nonce: rlp.val_at(1)?,
-			max_priority_fee_per_gas: rlp.val_at(2)?,
-			max_fee_per_gas: rlp.val_at(3)?,
-			gas_limit: rlp.val_at(4)?,
-			action: rlp.val_at(5)?,
-			value: rlp.val_at(6)?,
-			input: rlp.val_at(7)?,
-			access_list: rlp.list_at(8)?,
-			odd_y_parity: rlp.val_at(9)?,
+			chain_id: rlp.val_at(0).map_err(DecodeError::field("chain_id"))?,
+			nonce: rlp.val_at(1).map_err(DecodeError::field("nonce"))?,
+			max_priority_fee_per_gas: rlp
+				.val_at(2)
+				.map_err(DecodeError::field("max_priority_fee_per_gas"))?,
+			max_fee_per_gas: rlp
+				.val_at(3)
+				.map_err(DecodeError::field("max_fee_per_gas"))?,
+			gas_limit: rlp.val_at(4).map_err(DecodeError::field("gas_limit"))?,
+			to: rlp.val_at(5).map_err(DecodeError::field("to"))?,
+			value: rlp.val_at(6).map_err(DecodeError::field("value"))?,
+			input: rlp.val_at(7).map_err(DecodeError::field("input"))?,
+			access_list: rlp.list_at(8).map_err(DecodeError::field("access_list"))?,
+			authorization_list: rlp
+				.list_at(9)
+				.map_err(DecodeError::field("authorization_list"))?,
+			odd_y_parity: rlp.val_at(10).map_err(DecodeError::field("odd_y_parity"))?,
 			r: {
 				let mut rarr = [0_u8; 32];
-				rlp.val_at::<U256>(10)?.to_big_endian(&mut rarr);
+				rlp.val_at::<U256>(11)
+					.map_err(DecodeError::field("r"))?
+					.to_big_endian(&mut rarr);
 				H256::from(rarr)
 			},
 			s: {
 				let mut sarr = [0_u8; 32];
-				rlp.val_at::<U256>(11)?.to_big_endian(&mut sarr);
+				rlp.val_at::<U256>(12)
+					.map_err(DecodeError::field("s"))?
+					.to_big_endian(&mut sarr);
 				H256::from(sarr)
 			},
 		})
 	}
 }
 
+impl Decodable for EIP7702Transaction {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Self::decode_rlp(rlp).map_err(Into::into)
+	}
+}
+
 pub type TransactionV0 = LegacyTransaction;
 
 impl EnvelopedEncodable for TransactionV0 {
@@ -605,10 +1061,10 @@ impl EnvelopedEncodable for TransactionV0 {
 }
 
 impl EnvelopedDecodable for TransactionV0 {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
 
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
-		Ok(rlp::decode(bytes)?)
+		Ok(LegacyTransaction::decode_rlp(&Rlp::new(bytes))?)
 	}
 }
 
@@ -651,27 +1107,27 @@ impl EnvelopedEncodable for TransactionV1 {
 }
 
 impl EnvelopedDecodable for TransactionV1 {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
 
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
 		if bytes.is_empty() {
-			return Err(EnvelopedDecoderError::UnknownTypeId);
+			return Err(DecoderError::RlpIsTooShort.into());
 		}
 
 		let first = bytes[0];
 
 		let rlp = Rlp::new(bytes);
 		if rlp.is_list() {
-			return Ok(Self::Legacy(rlp.as_val()?));
+			return Ok(Self::Legacy(LegacyTransaction::decode_rlp(&rlp)?));
 		}
 
 		let s = &bytes[1..];
 
 		if first == 0x01 {
-			return Ok(Self::EIP2930(rlp::decode(s)?));
+			return Ok(Self::EIP2930(EIP2930Transaction::decode_rlp(&Rlp::new(s))?));
 		}
 
-		Err(DecoderError::Custom("invalid tx type").into())
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
 	}
 }
 
@@ -719,31 +1175,202 @@ impl EnvelopedEncodable for TransactionV2 {
 }
 
 impl EnvelopedDecodable for TransactionV2 {
-	type PayloadDecoderError = DecoderError;
+	type PayloadDecoderError = DecodeError;
+
+	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
+		if bytes.is_empty() {
+			return Err(DecoderError::RlpIsTooShort.into());
+		}
+
+		let first = bytes[0];
+
+		let rlp = Rlp::new(bytes);
+		if rlp.is_list() {
+			return Ok(Self::Legacy(LegacyTransaction::decode_rlp(&rlp)?));
+		}
+
+		let s = &bytes[1..];
+
+		if first == 0x01 {
+			return Ok(Self::EIP2930(EIP2930Transaction::decode_rlp(&Rlp::new(s))?));
+		}
+
+		if first == 0x02 {
+			return Ok(Self::EIP1559(EIP1559Transaction::decode_rlp(&Rlp::new(s))?));
+		}
+
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionV3 {
+	/// Legacy transaction type
+	Legacy(LegacyTransaction),
+	/// EIP-2930 transaction
+	EIP2930(EIP2930Transaction),
+	/// EIP-1559 transaction
+	EIP1559(EIP1559Transaction),
+	/// EIP-4844 transaction
+	EIP4844(EIP4844Transaction),
+}
+
+impl TransactionV3 {
+	pub fn hash(&self) -> H256 {
+		match self {
+			TransactionV3::Legacy(t) => t.hash(),
+			TransactionV3::EIP2930(t) => t.hash(),
+			TransactionV3::EIP1559(t) => t.hash(),
+			TransactionV3::EIP4844(t) => t.hash(),
+		}
+	}
+}
+
+impl EnvelopedEncodable for TransactionV3 {
+	fn type_id(&self) -> Option<u8> {
+		match self {
+			Self::Legacy(_) => None,
+			Self::EIP2930(_) => Some(1),
+			Self::EIP1559(_) => Some(2),
+			Self::EIP4844(_) => Some(3),
+		}
+	}
+
+	fn encode_payload(&self) -> BytesMut {
+		match self {
+			Self::Legacy(tx) => rlp::encode(tx),
+			Self::EIP2930(tx) => rlp::encode(tx),
+			Self::EIP1559(tx) => rlp::encode(tx),
+			Self::EIP4844(tx) => rlp::encode(tx),
+		}
+	}
+}
+
+impl EnvelopedDecodable for TransactionV3 {
+	type PayloadDecoderError = DecodeError;
+
+	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
+		if bytes.is_empty() {
+			return Err(DecoderError::RlpIsTooShort.into());
+		}
+
+		let first = bytes[0];
+
+		let rlp = Rlp::new(bytes);
+		if rlp.is_list() {
+			return Ok(Self::Legacy(LegacyTransaction::decode_rlp(&rlp)?));
+		}
+
+		let s = &bytes[1..];
+
+		if first == 0x01 {
+			return Ok(Self::EIP2930(EIP2930Transaction::decode_rlp(&Rlp::new(s))?));
+		}
+
+		if first == 0x02 {
+			return Ok(Self::EIP1559(EIP1559Transaction::decode_rlp(&Rlp::new(s))?));
+		}
+
+		if first == 0x03 {
+			return Ok(Self::EIP4844(EIP4844Transaction::decode_rlp(&Rlp::new(s))?));
+		}
+
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(feature = "with-serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionV4 {
+	/// Legacy transaction type
+	Legacy(LegacyTransaction),
+	/// EIP-2930 transaction
+	EIP2930(EIP2930Transaction),
+	/// EIP-1559 transaction
+	EIP1559(EIP1559Transaction),
+	/// EIP-4844 transaction
+	EIP4844(EIP4844Transaction),
+	/// EIP-7702 transaction
+	EIP7702(EIP7702Transaction),
+}
+
+impl TransactionV4 {
+	pub fn hash(&self) -> H256 {
+		match self {
+			TransactionV4::Legacy(t) => t.hash(),
+			TransactionV4::EIP2930(t) => t.hash(),
+			TransactionV4::EIP1559(t) => t.hash(),
+			TransactionV4::EIP4844(t) => t.hash(),
+			TransactionV4::EIP7702(t) => t.hash(),
+		}
+	}
+}
+
+impl EnvelopedEncodable for TransactionV4 {
+	fn type_id(&self) -> Option<u8> {
+		match self {
+			Self::Legacy(_) => None,
+			Self::EIP2930(_) => Some(1),
+			Self::EIP1559(_) => Some(2),
+			Self::EIP4844(_) => Some(3),
+			Self::EIP7702(_) => Some(4),
+		}
+	}
+
+	fn encode_payload(&self) -> BytesMut {
+		match self {
+			Self::Legacy(tx) => rlp::encode(tx),
+			Self::EIP2930(tx) => rlp::encode(tx),
+			Self::EIP1559(tx) => rlp::encode(tx),
+			Self::EIP4844(tx) => rlp::encode(tx),
+			Self::EIP7702(tx) => rlp::encode(tx),
+		}
+	}
+}
+
+impl EnvelopedDecodable for TransactionV4 {
+	type PayloadDecoderError = DecodeError;
 
 	fn decode(bytes: &[u8]) -> Result<Self, EnvelopedDecoderError<Self::PayloadDecoderError>> {
 		if bytes.is_empty() {
-			return Err(EnvelopedDecoderError::UnknownTypeId);
+			return Err(DecoderError::RlpIsTooShort.into());
 		}
 
 		let first = bytes[0];
 
 		let rlp = Rlp::new(bytes);
 		if rlp.is_list() {
-			return Ok(Self::Legacy(rlp.as_val()?));
+			return Ok(Self::Legacy(LegacyTransaction::decode_rlp(&rlp)?));
 		}
 
 		let s = &bytes[1..];
 
 		if first == 0x01 {
-			return Ok(Self::EIP2930(rlp::decode(s)?));
+			return Ok(Self::EIP2930(EIP2930Transaction::decode_rlp(&Rlp::new(s))?));
 		}
 
 		if first == 0x02 {
-			return Ok(Self::EIP1559(rlp::decode(s)?));
+			return Ok(Self::EIP1559(EIP1559Transaction::decode_rlp(&Rlp::new(s))?));
+		}
+
+		if first == 0x03 {
+			return Ok(Self::EIP4844(EIP4844Transaction::decode_rlp(&Rlp::new(s))?));
 		}
 
-		Err(DecoderError::Custom("invalid tx type").into())
+		if first == 0x04 {
+			return Ok(Self::EIP7702(EIP7702Transaction::decode_rlp(&Rlp::new(s))?));
+		}
+
+		Err(EnvelopedDecoderError::UnknownTypeId(first))
 	}
 }
 
@@ -768,11 +1395,149 @@ impl From<TransactionV1> for TransactionV2 {
 	}
 }
 
+impl From<LegacyTransaction> for TransactionV3 {
+	fn from(t: LegacyTransaction) -> Self {
+		TransactionV3::Legacy(t)
+	}
+}
+
+impl From<TransactionV1> for TransactionV3 {
+	fn from(t: TransactionV1) -> Self {
+		match t {
+			TransactionV1::Legacy(t) => TransactionV3::Legacy(t),
+			TransactionV1::EIP2930(t) => TransactionV3::EIP2930(t),
+		}
+	}
+}
+
+impl From<TransactionV2> for TransactionV3 {
+	fn from(t: TransactionV2) -> Self {
+		match t {
+			TransactionV2::Legacy(t) => TransactionV3::Legacy(t),
+			TransactionV2::EIP2930(t) => TransactionV3::EIP2930(t),
+			TransactionV2::EIP1559(t) => TransactionV3::EIP1559(t),
+		}
+	}
+}
+
+impl From<LegacyTransaction> for TransactionV4 {
+	fn from(t: LegacyTransaction) -> Self {
+		TransactionV4::Legacy(t)
+	}
+}
+
+impl From<TransactionV1> for TransactionV4 {
+	fn from(t: TransactionV1) -> Self {
+		match t {
+			TransactionV1::Legacy(t) => TransactionV4::Legacy(t),
+			TransactionV1::EIP2930(t) => TransactionV4::EIP2930(t),
+		}
+	}
+}
+
+impl From<TransactionV2> for TransactionV4 {
+	fn from(t: TransactionV2) -> Self {
+		match t {
+			TransactionV2::Legacy(t) => TransactionV4::Legacy(t),
+			TransactionV2::EIP2930(t) => TransactionV4::EIP2930(t),
+			TransactionV2::EIP1559(t) => TransactionV4::EIP1559(t),
+		}
+	}
+}
+
+impl From<TransactionV3> for TransactionV4 {
+	fn from(t: TransactionV3) -> Self {
+		match t {
+			TransactionV3::Legacy(t) => TransactionV4::Legacy(t),
+			TransactionV3::EIP2930(t) => TransactionV4::EIP2930(t),
+			TransactionV3::EIP1559(t) => TransactionV4::EIP1559(t),
+			TransactionV3::EIP4844(t) => TransactionV4::EIP4844(t),
+		}
+	}
+}
+
 pub type TransactionAny = TransactionV2;
 
+fn created_address(action: &TransactionAction, sender: Address, nonce: U256) -> Option<Address> {
+	match action {
+		TransactionAction::Create => Some(crate::util::contract_address(sender, nonce)),
+		TransactionAction::Call(_) => None,
+	}
+}
+
+macro_rules! impl_created_address {
+	($($tx:ty),*) => {
+		$(
+			impl $tx {
+				/// Address of the contract deployed by the transaction if it is sent by `sender`
+				/// and creates a contract.
+				pub fn created_address(&self, sender: Address) -> Option<Address> {
+					created_address(&self.action, sender, self.nonce)
+				}
+			}
+		)*
+	};
+}
+
+impl_created_address!(LegacyTransaction, EIP2930Transaction, EIP1559Transaction);
+
+macro_rules! impl_never_creates {
+	($($tx:ty),*) => {
+		$(
+			impl $tx {
+				/// Always `None`: the transaction type has no contract creation form.
+				pub fn created_address(&self, _sender: Address) -> Option<Address> {
+					None
+				}
+			}
+		)*
+	};
+}
+
+impl_never_creates!(EIP4844Transaction, EIP7702Transaction);
+
+macro_rules! impl_enum_created_address {
+	($($enum:ident { $($variant:ident),* }),*) => {
+		$(
+			impl $enum {
+				/// Address of the contract deployed by the transaction if it is sent by `sender`
+				/// and creates a contract.
+				pub fn created_address(&self, sender: Address) -> Option<Address> {
+					match self {
+						$($enum::$variant(t) => t.created_address(sender),)*
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_enum_created_address!(
+	TransactionV1 { Legacy, EIP2930 },
+	TransactionV2 {
+		Legacy,
+		EIP2930,
+		EIP1559
+	},
+	TransactionV3 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844
+	},
+	TransactionV4 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844,
+		EIP7702
+	}
+);
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
 	use super::*;
+	use alloc::vec;
 	use hex_literal::hex;
 
 	#[test]
@@ -782,6 +1547,8 @@ mod tests {
 		<TransactionV0 as EnvelopedDecodable>::decode(&bytes).unwrap();
 		<TransactionV1 as EnvelopedDecodable>::decode(&bytes).unwrap();
 		<TransactionV2 as EnvelopedDecodable>::decode(&bytes).unwrap();
+		<TransactionV3 as EnvelopedDecodable>::decode(&bytes).unwrap();
+		<TransactionV4 as EnvelopedDecodable>::decode(&bytes).unwrap();
 	}
 
 	#[test]
@@ -880,4 +1647,117 @@ mod tests {
 			<TransactionV2 as EnvelopedDecodable>::decode(&tx.encode()).unwrap()
 		);
 	}
+
+	/// Type-3 transaction signed with the EIP-155 example key `0x4646..46`, encoded and hashed
+	/// by a separate Keccak-256 and RLP implementation following EIP-4844.
+	pub(crate) const EIP4844_TX: &[u8] = &hex!("03f897010c84773594008509502f9000830186a09435353535353535353535353535353535353535358084deadbeefc084b2d05e00e1a001a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a701a01ea74ba02237afb7ce6b6f01955ee43e8b763ed6559b5619ffe6592a38281678a02b96b873bfdba87043b8acdf2f17356a524721aa35acf44da2c71be8e1922023");
+
+	/// Type-4 transaction from the same key, carrying an authorization that key also signed.
+	pub(crate) const EIP7702_TX: &[u8] = &hex!("04f8ca010e84773594008509502f9000830186a0949d8a62f656a8d1615c1294fd71e9cfb3e4855a4f8080c0f85cf85a019400000000000000000000000000000000000000ff0d01a0e7ee3fa6f70fe7f37323140eb1693226e63ee7b704795e8aaa4ff2372b47ce92a03521af19f500162426d01b7b69a52f3e5a70e79769ac6e80630d6e6eff42cc6101a04cf2bcc9b199d4cdccd90bd4ed73ee69495ae0b52bbf9357c5fa049f389ba502a07bec36d7d45894630894495b02cdc4884e9fda0b02c8c4ce97409b14b381e757");
+
+	#[test]
+	fn transaction_v3() {
+		let tx = <TransactionV3 as EnvelopedDecodable>::decode(EIP4844_TX).unwrap();
+		let inner = match &tx {
+			TransactionV3::EIP4844(t) => t.clone(),
+			_ => panic!("expected an EIP-4844 transaction"),
+		};
+		assert_eq!(inner.nonce, 12.into());
+		assert_eq!(inner.input, hex!("deadbeef").to_vec());
+		assert_eq!(inner.max_fee_per_blob_gas, 3_000_000_000_u64.into());
+		assert_eq!(
+			inner.blob_versioned_hashes,
+			vec![H256::from(hex!(
+				"01a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7a7"
+			))]
+		);
+
+		assert_eq!(
+			tx.hash(),
+			H256::from(hex!(
+				"07b6d4cb9bf43e6be8b2ea326a7c241fbce6d7a54a418bf2d8911e3fcdf77307"
+			))
+		);
+		assert_eq!(
+			EIP4844TransactionMessage::from(inner).hash(),
+			H256::from(hex!(
+				"5e798c521a1fb468a6e835574468895ba0f6d1b66496fac2e8d97a82809f9270"
+			))
+		);
+		assert_eq!(tx.encode().to_vec(), EIP4844_TX);
+		assert!(<TransactionV2 as EnvelopedDecodable>::decode(EIP4844_TX).is_err());
+	}
+
+	#[test]
+	fn transaction_v4() {
+		let tx = <TransactionV4 as EnvelopedDecodable>::decode(EIP7702_TX).unwrap();
+		let inner = match &tx {
+			TransactionV4::EIP7702(t) => t.clone(),
+			_ => panic!("expected an EIP-7702 transaction"),
+		};
+		assert_eq!(inner.nonce, 14.into());
+		assert_eq!(
+			inner.authorization_list,
+			vec![Authorization {
+				chain_id: 1.into(),
+				address: hex!("00000000000000000000000000000000000000ff").into(),
+				nonce: 13,
+				y_parity: 1,
+				r: hex!("e7ee3fa6f70fe7f37323140eb1693226e63ee7b704795e8aaa4ff2372b47ce92").into(),
+				s: hex!("3521af19f500162426d01b7b69a52f3e5a70e79769ac6e80630d6e6eff42cc61").into(),
+			}]
+		);
+
+		assert_eq!(
+			tx.hash(),
+			H256::from(hex!(
+				"f4a21dcf92ef938599638883458a3e7e6f6b18d6447e203cde65f2d8bc224f6b"
+			))
+		);
+		assert_eq!(
+			AuthorizationMessage::from(inner.authorization_list[0].clone()).hash(),
+			H256::from(hex!(
+				"cd0856a6ecd0209d1e75c001cb923ed53f8dbdd361f56bd4b151dd4633285106"
+			))
+		);
+		assert_eq!(
+			EIP7702TransactionMessage::from(inner).hash(),
+			H256::from(hex!(
+				"9635020046a2fc7c30d0fda3c66b0a486fefe6a5ec42c4015a5b02ea9bbb4e79"
+			))
+		);
+		assert_eq!(tx.encode().to_vec(), EIP7702_TX);
+		assert!(<TransactionV3 as EnvelopedDecodable>::decode(EIP7702_TX).is_err());
+	}
+
+	#[test]
+	fn decode_reports_unknown_type_and_failing_field() {
+		assert_eq!(
+			<TransactionV2 as EnvelopedDecodable>::decode(&[0x03, 0xc0]),
+			Err(EnvelopedDecoderError::UnknownTypeId(0x03))
+		);
+
+		let mut stream = RlpStream::new_list(12);
+		for _ in 0..8 {
+			stream.append(&0_u8);
+		}
+		// The access list item address is three bytes long.
+		stream.begin_list(1);
+		stream.begin_list(2);
+		stream.append(&vec![1_u8, 2, 3]);
+		stream.begin_list(0);
+		stream.append(&false);
+		stream.append(&1_u8);
+		stream.append(&1_u8);
+		let mut bytes = vec![0x02];
+		bytes.extend_from_slice(&stream.out());
+
+		assert_eq!(
+			<TransactionV2 as EnvelopedDecodable>::decode(&bytes),
+			Err(EnvelopedDecoderError::Payload(DecodeError::Field {
+				field: "access_list",
+				error: DecoderError::RlpIsTooShort,
+			}))
+		);
+	}
 }
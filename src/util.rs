@@ -1,10 +1,15 @@
 //! Utility functions for Ethereum.
 
-use ethereum_types::H256;
+use ethereum_types::{Address, H256, U256};
 use hash256_std_hasher::Hash256StdHasher;
 use hash_db::Hasher;
+use rlp::RlpStream;
 use sha3::{Digest, Keccak256};
 
+mod trie;
+
+pub use trie::{verify_proof, MemoryTrie, ProofError};
+
 /// Concrete `Hasher` impl for the Keccak-256 hash
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct KeccakHasher;
@@ -48,3 +53,56 @@ where
 {
 	triehash::ordered_trie_root::<KeccakHasher, I>(input)
 }
+
+/// Address of a contract created by `sender` with a `CREATE` at account nonce `nonce`.
+pub fn contract_address(sender: Address, nonce: U256) -> Address {
+	let mut stream = RlpStream::new_list(2);
+	stream.append(&sender);
+	stream.append(&nonce);
+	Address::from_slice(&Keccak256::digest(stream.out())[12..])
+}
+
+/// Address of a contract created by `deployer` with a `CREATE2` (EIP-1014).
+pub fn create2_address(deployer: Address, salt: H256, init_code_hash: H256) -> Address {
+	let mut hasher = Keccak256::new();
+	hasher.update([0xff]);
+	hasher.update(deployer.as_bytes());
+	hasher.update(salt.as_bytes());
+	hasher.update(init_code_hash.as_bytes());
+	Address::from_slice(&hasher.finalize()[12..])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	#[test]
+	fn derives_contract_addresses() {
+		let sender = Address::from(hex!("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"));
+		assert_eq!(
+			contract_address(sender, 0.into()),
+			Address::from(hex!("cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"))
+		);
+		assert_eq!(
+			contract_address(sender, 1.into()),
+			Address::from(hex!("343c43a37d37dff08ae8c4a11544c718abb4fcf8"))
+		);
+
+		// Examples from EIP-1014.
+		assert_eq!(
+			create2_address(Address::zero(), H256::zero(), KeccakHasher::hash(&[0x00])),
+			Address::from(hex!("4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"))
+		);
+		assert_eq!(
+			create2_address(
+				Address::from(hex!("00000000000000000000000000000000deadbeef")),
+				H256::from(hex!(
+					"00000000000000000000000000000000000000000000000000000000cafebabe"
+				)),
+				KeccakHasher::hash(&hex!("deadbeef")),
+			),
+			Address::from(hex!("60f3f640a8508fc6a86d45df051962668e1e8ac7"))
+		);
+	}
+}
@@ -0,0 +1,484 @@
+//! In-memory Merkle Patricia Trie with inclusion proofs.
+
+use super::KeccakHasher;
+use alloc::{boxed::Box, vec::Vec};
+use core::mem;
+use ethereum_types::H256;
+use hash_db::Hasher;
+use rlp::{DecoderError, Rlp, RlpStream};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+enum Node {
+	#[default]
+	Empty,
+	Leaf(Vec<u8>, Vec<u8>),
+	Extension(Vec<u8>, Box<Node>),
+	Branch(Box<[Node; 16]>, Option<Vec<u8>>),
+}
+
+impl Node {
+	fn empty_branch() -> Self {
+		Node::Branch(Box::default(), None)
+	}
+
+	fn encode(&self) -> Vec<u8> {
+		match self {
+			Node::Empty => rlp::NULL_RLP.to_vec(),
+			Node::Leaf(path, value) => {
+				let mut s = RlpStream::new_list(2);
+				s.append(&hex_prefix(path, true));
+				s.append(value);
+				s.out().to_vec()
+			}
+			Node::Extension(path, child) => {
+				let mut s = RlpStream::new_list(2);
+				s.append(&hex_prefix(path, false));
+				append_child(&mut s, child);
+				s.out().to_vec()
+			}
+			Node::Branch(children, value) => {
+				let mut s = RlpStream::new_list(17);
+				for child in children.iter() {
+					append_child(&mut s, child);
+				}
+				match value {
+					Some(value) => s.append(value),
+					None => s.append_empty_data(),
+				};
+				s.out().to_vec()
+			}
+		}
+	}
+
+	fn get(&self, path: &[u8]) -> Option<&[u8]> {
+		match self {
+			Node::Empty => None,
+			Node::Leaf(leaf_path, value) => (leaf_path[..] == *path).then_some(&value[..]),
+			Node::Extension(ext_path, child) => path
+				.strip_prefix(&ext_path[..])
+				.and_then(|rest| child.get(rest)),
+			Node::Branch(children, value) => match path.split_first() {
+				None => value.as_deref(),
+				Some((nibble, rest)) => children[*nibble as usize].get(rest),
+			},
+		}
+	}
+
+	fn insert(self, path: &[u8], value: Vec<u8>) -> (Node, Option<Vec<u8>>) {
+		match self {
+			Node::Empty => (Node::Leaf(path.to_vec(), value), None),
+			Node::Leaf(leaf_path, old) if leaf_path[..] == *path => {
+				(Node::Leaf(leaf_path, value), Some(old))
+			}
+			Node::Leaf(leaf_path, old) => {
+				let common = common_prefix(&leaf_path, path);
+				let (branch, _) = Node::empty_branch().insert(&leaf_path[common..], old);
+				let (branch, _) = branch.insert(&path[common..], value);
+				(with_extension(&path[..common], branch), None)
+			}
+			Node::Extension(ext_path, child) => {
+				let common = common_prefix(&ext_path, path);
+				if common == ext_path.len() {
+					let (child, old) = child.insert(&path[common..], value);
+					return (Node::Extension(ext_path, Box::new(child)), old);
+				}
+
+				let mut children: [Node; 16] = Default::default();
+				children[ext_path[common] as usize] =
+					with_extension(&ext_path[common + 1..], *child);
+				let (branch, _) =
+					Node::Branch(Box::new(children), None).insert(&path[common..], value);
+				(with_extension(&path[..common], branch), None)
+			}
+			Node::Branch(mut children, branch_value) => match path.split_first() {
+				None => (Node::Branch(children, Some(value)), branch_value),
+				Some((nibble, rest)) => {
+					let slot = &mut children[*nibble as usize];
+					let (child, old) = mem::take(slot).insert(rest, value);
+					*slot = child;
+					(Node::Branch(children, branch_value), old)
+				}
+			},
+		}
+	}
+
+	fn remove(self, path: &[u8]) -> (Node, Option<Vec<u8>>) {
+		match self {
+			Node::Leaf(leaf_path, value) if leaf_path[..] == *path => (Node::Empty, Some(value)),
+			Node::Extension(ext_path, child) => match path.strip_prefix(&ext_path[..]) {
+				Some(rest) => {
+					let (child, old) = child.remove(rest);
+					(with_extension(&ext_path, child), old)
+				}
+				None => (Node::Extension(ext_path, child), None),
+			},
+			Node::Branch(mut children, mut value) => {
+				let old = match path.split_first() {
+					None => value.take(),
+					Some((nibble, rest)) => {
+						let slot = &mut children[*nibble as usize];
+						let (child, old) = mem::take(slot).remove(rest);
+						*slot = child;
+						old
+					}
+				};
+				(collapse_branch(children, value), old)
+			}
+			node => (node, None),
+		}
+	}
+
+	fn prove(&self, path: &[u8], proof: &mut Vec<Vec<u8>>) {
+		let next = match self {
+			Node::Empty | Node::Leaf(..) => None,
+			Node::Extension(ext_path, child) => path
+				.strip_prefix(&ext_path[..])
+				.map(|rest| (&**child, rest)),
+			Node::Branch(children, _) => path
+				.split_first()
+				.map(|(nibble, rest)| (&children[*nibble as usize], rest)),
+		};
+
+		if let Some((child, rest)) = next {
+			let encoded = child.encode();
+			if encoded.len() >= 32 {
+				proof.push(encoded);
+			}
+			child.prove(rest, proof);
+		}
+	}
+}
+
+/// Prefix `node` with an extension over `path`, merging it into `node` where possible.
+fn with_extension(path: &[u8], node: Node) -> Node {
+	if path.is_empty() {
+		return node;
+	}
+
+	match node {
+		Node::Empty => Node::Empty,
+		Node::Leaf(rest, value) => Node::Leaf([path, &rest[..]].concat(), value),
+		Node::Extension(rest, child) => Node::Extension([path, &rest[..]].concat(), child),
+		branch => Node::Extension(path.to_vec(), Box::new(branch)),
+	}
+}
+
+/// Turn a branch left with fewer than two entries into the equivalent smaller node.
+fn collapse_branch(mut children: Box<[Node; 16]>, value: Option<Vec<u8>>) -> Node {
+	let mut used = children
+		.iter()
+		.enumerate()
+		.filter(|(_, child)| **child != Node::Empty)
+		.map(|(index, _)| index);
+
+	match (used.next(), used.next(), value) {
+		(None, _, None) => Node::Empty,
+		(None, _, Some(value)) => Node::Leaf(Vec::new(), value),
+		(Some(index), None, None) => {
+			with_extension(&[index as u8], mem::take(&mut children[index]))
+		}
+		(_, _, value) => Node::Branch(children, value),
+	}
+}
+
+fn append_child(s: &mut RlpStream, child: &Node) {
+	match child {
+		Node::Empty => {
+			s.append_empty_data();
+		}
+		child => {
+			let encoded = child.encode();
+			if encoded.len() < 32 {
+				s.append_raw(&encoded, 1);
+			} else {
+				s.append(&KeccakHasher::hash(&encoded));
+			}
+		}
+	}
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+	a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+fn nibbles(key: &[u8]) -> Vec<u8> {
+	key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+fn hex_prefix(path: &[u8], leaf: bool) -> Vec<u8> {
+	let flag = if leaf { 0x20 } else { 0x00 };
+	let mut out = Vec::with_capacity(path.len() / 2 + 1);
+	let rest = if path.len() % 2 == 1 {
+		out.push(flag | 0x10 | path[0]);
+		&path[1..]
+	} else {
+		out.push(flag);
+		path
+	};
+	out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+	out
+}
+
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(Vec<u8>, bool), ProofError> {
+	let (first, rest) = encoded.split_first().ok_or(ProofError::InvalidNode)?;
+	let leaf = first & 0x20 != 0;
+	let mut path = Vec::with_capacity(rest.len() * 2 + 1);
+	if first & 0x10 != 0 {
+		path.push(first & 0x0f);
+	}
+	path.extend(nibbles(rest));
+	Ok((path, leaf))
+}
+
+/// Error returned by [`verify_proof`] when a proof is malformed or does not match the root.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProofError {
+	/// The proof ends before the key's path is resolved.
+	MissingNode,
+	/// A proof node does not hash to the reference held by its parent.
+	HashMismatch,
+	/// A proof node is not a valid trie node.
+	InvalidNode,
+	/// A proof node is not valid RLP.
+	Rlp(DecoderError),
+	/// The proof holds nodes beyond those needed to resolve the key.
+	UnusedNodes,
+}
+
+impl From<DecoderError> for ProofError {
+	fn from(e: DecoderError) -> Self {
+		Self::Rlp(e)
+	}
+}
+
+/// Merkle Patricia Trie kept in memory, hashed with Keccak-256.
+///
+/// Roots match [`trie_root`](super::trie_root) over the same entries. Empty values are not
+/// stored: inserting one removes the key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryTrie {
+	root: Node,
+}
+
+impl MemoryTrie {
+	/// Create an empty trie.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Whether the trie holds no entries.
+	pub fn is_empty(&self) -> bool {
+		self.root == Node::Empty
+	}
+
+	/// Value stored under `key`.
+	pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+		self.root.get(&nibbles(key))
+	}
+
+	/// Store `value` under `key`, returning the previous value.
+	pub fn insert(&mut self, key: &[u8], value: Vec<u8>) -> Option<Vec<u8>> {
+		if value.is_empty() {
+			return self.remove(key);
+		}
+
+		let (root, old) = mem::take(&mut self.root).insert(&nibbles(key), value);
+		self.root = root;
+		old
+	}
+
+	/// Remove `key`, returning its value.
+	pub fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		let (root, old) = mem::take(&mut self.root).remove(&nibbles(key));
+		self.root = root;
+		old
+	}
+
+	/// Root hash of the trie.
+	pub fn root(&self) -> H256 {
+		KeccakHasher::hash(&self.root.encode())
+	}
+
+	/// RLP-encoded nodes on the path to `key`, starting with the root node. Nodes embedded in
+	/// their parent are not repeated. The proof shows absence when `key` is not in the trie.
+	pub fn prove(&self, key: &[u8]) -> Vec<Vec<u8>> {
+		let mut proof = alloc::vec![self.root.encode()];
+		self.root.prove(&nibbles(key), &mut proof);
+		proof
+	}
+}
+
+/// Check a proof produced by [`MemoryTrie::prove`] (or `eth_getProof`) against `root`.
+///
+/// Returns the value stored under `key`, or `None` if the proof shows that the key is absent.
+pub fn verify_proof(
+	root: H256,
+	key: &[u8],
+	proof: &[Vec<u8>],
+) -> Result<Option<Vec<u8>>, ProofError> {
+	let mut proof = proof.iter();
+	let value = resolve_proof(root, key, &mut proof)?;
+	if proof.next().is_some() {
+		return Err(ProofError::UnusedNodes);
+	}
+	Ok(value)
+}
+
+fn resolve_proof<'a>(
+	root: H256,
+	key: &[u8],
+	proof: &mut impl Iterator<Item = &'a Vec<u8>>,
+) -> Result<Option<Vec<u8>>, ProofError> {
+	let path = nibbles(key);
+	let mut path = &path[..];
+	let mut expected = root;
+	let mut node = next_proof_node(proof, expected)?;
+
+	loop {
+		let rlp = Rlp::new(&node);
+		let child = match rlp.item_count()? {
+			0 if rlp.is_data() => return Ok(None),
+			17 => match path.split_first() {
+				None => {
+					let value: Vec<u8> = rlp.at(16)?.data()?.to_vec();
+					return Ok((!value.is_empty()).then_some(value));
+				}
+				Some((nibble, rest)) => {
+					path = rest;
+					rlp.at(*nibble as usize)?
+				}
+			},
+			2 => {
+				let (node_path, leaf) = decode_hex_prefix(rlp.at(0)?.data()?)?;
+				if leaf {
+					if node_path[..] != *path {
+						return Ok(None);
+					}
+					return Ok(Some(rlp.at(1)?.data()?.to_vec()));
+				}
+				match path.strip_prefix(&node_path[..]) {
+					Some(rest) => {
+						path = rest;
+						rlp.at(1)?
+					}
+					None => return Ok(None),
+				}
+			}
+			_ => return Err(ProofError::InvalidNode),
+		};
+
+		node = if child.is_list() {
+			child.as_raw().to_vec()
+		} else {
+			match child.data()?.len() {
+				0 => return Ok(None),
+				32 => {
+					expected = H256::from_slice(child.data()?);
+					next_proof_node(proof, expected)?
+				}
+				_ => return Err(ProofError::InvalidNode),
+			}
+		};
+	}
+}
+
+fn next_proof_node<'a>(
+	proof: &mut impl Iterator<Item = &'a Vec<u8>>,
+	expected: H256,
+) -> Result<Vec<u8>, ProofError> {
+	let node = proof.next().ok_or(ProofError::MissingNode)?;
+	if KeccakHasher::hash(node) != expected {
+		return Err(ProofError::HashMismatch);
+	}
+	Ok(node.clone())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::trie_root;
+
+	fn entries() -> Vec<(Vec<u8>, Vec<u8>)> {
+		(0u32..64)
+			.map(|i| {
+				(
+					rlp::encode(&(i * 37 % 301)).to_vec(),
+					alloc::vec![i as u8 + 1; i as usize % 40 + 1],
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn matches_trie_root() {
+		let mut trie = MemoryTrie::new();
+		assert_eq!(trie.root(), trie_root::<_, Vec<u8>, Vec<u8>>(Vec::new()));
+
+		let entries = entries();
+		for (key, value) in &entries {
+			assert_eq!(trie.insert(key, value.clone()), None);
+		}
+		assert_eq!(trie.root(), trie_root(entries.clone()));
+
+		for (key, value) in &entries[..20] {
+			assert_eq!(trie.remove(key).as_ref(), Some(value));
+		}
+		assert_eq!(trie.root(), trie_root(entries[20..].to_vec()));
+		assert_eq!(trie.get(&entries[30].0), Some(&entries[30].1[..]));
+		assert_eq!(trie.get(&entries[0].0), None);
+
+		for (key, _) in &entries[20..] {
+			trie.remove(key);
+		}
+		assert!(trie.is_empty());
+	}
+
+	#[test]
+	fn proves_inclusion_and_absence() {
+		let mut trie = MemoryTrie::new();
+		let entries = entries();
+		for (key, value) in &entries {
+			trie.insert(key, value.clone());
+		}
+		let root = trie.root();
+
+		for (key, value) in &entries {
+			assert_eq!(
+				verify_proof(root, key, &trie.prove(key)),
+				Ok(Some(value.clone()))
+			);
+		}
+
+		let missing = rlp::encode(&1000u32);
+		assert_eq!(
+			verify_proof(root, &missing, &trie.prove(&missing)),
+			Ok(None)
+		);
+
+		let key = &entries[5].0;
+		let mut proof = trie.prove(key);
+		let mut padded = proof.clone();
+		padded.push(trie.prove(&entries[6].0).pop().unwrap());
+		assert_eq!(
+			verify_proof(root, key, &padded),
+			Err(ProofError::UnusedNodes)
+		);
+		let mut padded = trie.prove(&missing);
+		padded.push(proof[0].clone());
+		assert_eq!(
+			verify_proof(root, &missing, &padded),
+			Err(ProofError::UnusedNodes)
+		);
+
+		assert_eq!(
+			verify_proof(root, key, &proof[..proof.len() - 1]),
+			Err(ProofError::MissingNode)
+		);
+		let last = proof.last_mut().unwrap();
+		*last.last_mut().unwrap() ^= 1;
+		assert_eq!(
+			verify_proof(root, key, &proof),
+			Err(ProofError::HashMismatch)
+		);
+	}
+}
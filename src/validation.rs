@@ -0,0 +1,591 @@
+//! Consensus checks that need no state beyond the headers and transactions involved.
+
+use crate::{
+	calc_next_base_fee, BaseFeeParams, ChainSpec, EIP1559Transaction, EIP2930Transaction,
+	EIP4844Transaction, EIP7702Transaction, Fork, Header, IntrinsicGas, LegacyTransaction,
+	TransactionAction, TransactionSignature, TransactionV1, TransactionV2, TransactionV3,
+	TransactionV4,
+};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use ethereum_types::{H256, U256};
+
+/// Base fee of the first London block.
+pub const INITIAL_BASE_FEE: u64 = 1_000_000_000;
+
+/// Maximum length of `extra_data`.
+pub const MAX_EXTRA_DATA_SIZE: usize = 32;
+
+/// Lowest gas limit a block may have.
+pub const MIN_GAS_LIMIT: u64 = 5_000;
+
+/// A gas limit may change by less than `parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR` per block.
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 1_024;
+
+/// Largest initcode accepted in a contract creation from Shanghai (EIP-3860).
+pub const MAX_INITCODE_SIZE: usize = 49_152;
+
+/// Rule broken by a header with respect to its parent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeaderViolation {
+	/// `parent_hash` is not the hash of the parent.
+	ParentHashMismatch { expected: H256, actual: H256 },
+	/// `number` is not one more than the parent's.
+	NumberMismatch { expected: U256, actual: U256 },
+	/// `timestamp` is not later than the parent's.
+	TimestampNotIncreasing { parent: u64, actual: u64 },
+	/// `gas_limit` changed by `parent_gas_limit / 1024` or more.
+	GasLimitOutOfBounds { parent: U256, actual: U256 },
+	/// `gas_limit` is below [`MIN_GAS_LIMIT`].
+	GasLimitTooLow { actual: U256 },
+	/// `gas_used` exceeds `gas_limit`.
+	GasUsedExceedsLimit { gas_used: U256, gas_limit: U256 },
+	/// `extra_data` is longer than [`MAX_EXTRA_DATA_SIZE`].
+	ExtraDataTooLong { len: usize },
+	/// A London header has no base fee.
+	MissingBaseFee,
+	/// A pre-London header has a base fee.
+	UnexpectedBaseFee,
+	/// The base fee does not follow from the parent under EIP-1559.
+	BaseFeeMismatch { expected: U256, actual: U256 },
+}
+
+fn block_number(header: &Header) -> u64 {
+	u64::try_from(header.number).unwrap_or(u64::MAX)
+}
+
+fn is_london(header: &Header, spec: &ChainSpec) -> bool {
+	spec.is_active(Fork::London, block_number(header), header.timestamp)
+}
+
+impl Header {
+	/// Check this header against its parent under the rules of `spec`, returning every rule
+	/// that is broken.
+	pub fn validate_against_parent(
+		&self,
+		parent: &Header,
+		spec: &ChainSpec,
+	) -> Result<(), Vec<HeaderViolation>> {
+		let mut violations = Vec::new();
+
+		let parent_hash = parent.hash();
+		if self.parent_hash != parent_hash {
+			violations.push(HeaderViolation::ParentHashMismatch {
+				expected: parent_hash,
+				actual: self.parent_hash,
+			});
+		}
+
+		let number = parent.number.saturating_add(U256::one());
+		if self.number != number {
+			violations.push(HeaderViolation::NumberMismatch {
+				expected: number,
+				actual: self.number,
+			});
+		}
+
+		if self.timestamp <= parent.timestamp {
+			violations.push(HeaderViolation::TimestampNotIncreasing {
+				parent: parent.timestamp,
+				actual: self.timestamp,
+			});
+		}
+
+		let london = is_london(self, spec);
+		let parent_london = is_london(parent, spec);
+
+		// The gas target stays the same across the London transition, so the limit doubles.
+		let parent_gas_limit = if london && !parent_london {
+			parent
+				.gas_limit
+				.saturating_mul(BaseFeeParams::LONDON.elasticity_multiplier.into())
+		} else {
+			parent.gas_limit
+		};
+		let gas_limit_change = if self.gas_limit > parent_gas_limit {
+			self.gas_limit - parent_gas_limit
+		} else {
+			parent_gas_limit - self.gas_limit
+		};
+		if gas_limit_change >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR {
+			violations.push(HeaderViolation::GasLimitOutOfBounds {
+				parent: parent_gas_limit,
+				actual: self.gas_limit,
+			});
+		}
+		if self.gas_limit < MIN_GAS_LIMIT.into() {
+			violations.push(HeaderViolation::GasLimitTooLow {
+				actual: self.gas_limit,
+			});
+		}
+
+		if self.gas_used > self.gas_limit {
+			violations.push(HeaderViolation::GasUsedExceedsLimit {
+				gas_used: self.gas_used,
+				gas_limit: self.gas_limit,
+			});
+		}
+
+		if self.extra_data.len() > MAX_EXTRA_DATA_SIZE {
+			violations.push(HeaderViolation::ExtraDataTooLong {
+				len: self.extra_data.len(),
+			});
+		}
+
+		match (london, self.base_fee) {
+			(false, None) => (),
+			(false, Some(_)) => violations.push(HeaderViolation::UnexpectedBaseFee),
+			(true, None) => violations.push(HeaderViolation::MissingBaseFee),
+			(true, Some(actual)) => {
+				let expected = match parent.base_fee {
+					Some(parent_base_fee) if parent_london => calc_next_base_fee(
+						parent.gas_used,
+						parent.gas_limit,
+						parent_base_fee,
+						BaseFeeParams::LONDON,
+					)
+					.expect("London base fee parameters are non-zero"),
+					_ => INITIAL_BASE_FEE.into(),
+				};
+				if actual != expected {
+					violations.push(HeaderViolation::BaseFeeMismatch { expected, actual });
+				}
+			}
+		}
+
+		if violations.is_empty() {
+			Ok(())
+		} else {
+			Err(violations)
+		}
+	}
+}
+
+/// Rule broken by a transaction, found without looking at the state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionViolation {
+	/// The transaction type is not valid in the fork.
+	UnsupportedType(u8),
+	/// `gas_limit` is below the intrinsic gas, including the EIP-7623 floor.
+	IntrinsicGasTooLow { gas_limit: U256, intrinsic: u64 },
+	/// `max_priority_fee_per_gas` exceeds `max_fee_per_gas`.
+	PriorityFeeExceedsMaxFee {
+		max_priority_fee_per_gas: U256,
+		max_fee_per_gas: U256,
+	},
+	/// The transaction is signed for another chain.
+	ChainIdMismatch { expected: u64, actual: u64 },
+	/// `r` or `s` is out of range, or the recovery id is invalid.
+	InvalidSignature,
+	/// `s` is in the upper half of the curve order, forbidden by EIP-2.
+	HighS,
+	/// A contract creation carries more than [`MAX_INITCODE_SIZE`] bytes of initcode.
+	InitcodeTooLarge { size: usize },
+	/// `gas_limit * max_fee_per_gas + value` does not fit in 256 bits.
+	CostOverflow,
+}
+
+struct TransactionFields<'a> {
+	type_id: Option<u8>,
+	chain_id: Option<u64>,
+	signature: Option<TransactionSignature>,
+	gas_limit: U256,
+	max_fee_per_gas: U256,
+	max_priority_fee_per_gas: Option<U256>,
+	value: U256,
+	action: TransactionAction,
+	input: &'a [u8],
+	intrinsic_gas: IntrinsicGas,
+}
+
+impl TransactionFields<'_> {
+	fn validate(self, chain_id: u64, fork: Fork) -> Result<(), Vec<TransactionViolation>> {
+		let mut violations = Vec::new();
+
+		if let Some(type_id) = self.type_id {
+			if !fork.allows_tx_type(Some(type_id)) {
+				violations.push(TransactionViolation::UnsupportedType(type_id));
+			}
+		}
+
+		let intrinsic = self.intrinsic_gas.min_gas_limit();
+		if self.gas_limit < intrinsic.into() {
+			violations.push(TransactionViolation::IntrinsicGasTooLow {
+				gas_limit: self.gas_limit,
+				intrinsic,
+			});
+		}
+
+		if let Some(max_priority_fee_per_gas) = self.max_priority_fee_per_gas {
+			if max_priority_fee_per_gas > self.max_fee_per_gas {
+				violations.push(TransactionViolation::PriorityFeeExceedsMaxFee {
+					max_priority_fee_per_gas,
+					max_fee_per_gas: self.max_fee_per_gas,
+				});
+			}
+		}
+
+		if let Some(actual) = self.chain_id {
+			if actual != chain_id {
+				violations.push(TransactionViolation::ChainIdMismatch {
+					expected: chain_id,
+					actual,
+				});
+			}
+		}
+
+		match &self.signature {
+			None => violations.push(TransactionViolation::InvalidSignature),
+			Some(signature) if fork >= Fork::Homestead && !signature.is_low_s() => {
+				violations.push(TransactionViolation::HighS)
+			}
+			Some(_) => (),
+		}
+
+		if self.action == TransactionAction::Create
+			&& fork >= Fork::Shanghai
+			&& self.input.len() > MAX_INITCODE_SIZE
+		{
+			violations.push(TransactionViolation::InitcodeTooLarge {
+				size: self.input.len(),
+			});
+		}
+
+		let overflows = self
+			.gas_limit
+			.checked_mul(self.max_fee_per_gas)
+			.and_then(|fee| fee.checked_add(self.value))
+			.is_none();
+		if overflows {
+			violations.push(TransactionViolation::CostOverflow);
+		}
+
+		if violations.is_empty() {
+			Ok(())
+		} else {
+			Err(violations)
+		}
+	}
+}
+
+fn typed_signature(odd_y_parity: bool, r: H256, s: H256) -> Option<TransactionSignature> {
+	TransactionSignature::new(27 + odd_y_parity as u64, r, s)
+}
+
+impl LegacyTransaction {
+	/// Check the transaction for a chain with `chain_id` under the rules of `fork`, returning
+	/// every rule that is broken. Transactions without EIP-155 replay protection are accepted
+	/// on any chain.
+	pub fn validate(&self, chain_id: u64, fork: Fork) -> Result<(), Vec<TransactionViolation>> {
+		TransactionFields {
+			type_id: None,
+			chain_id: self.signature.chain_id(),
+			signature: Some(self.signature.clone()),
+			gas_limit: self.gas_limit,
+			max_fee_per_gas: self.gas_price,
+			max_priority_fee_per_gas: None,
+			value: self.value,
+			action: self.action,
+			input: &self.input,
+			intrinsic_gas: self.intrinsic_gas(fork),
+		}
+		.validate(chain_id, fork)
+	}
+}
+
+impl EIP2930Transaction {
+	/// Check the transaction for a chain with `chain_id` under the rules of `fork`, returning
+	/// every rule that is broken.
+	pub fn validate(&self, chain_id: u64, fork: Fork) -> Result<(), Vec<TransactionViolation>> {
+		TransactionFields {
+			type_id: Some(1),
+			chain_id: Some(self.chain_id),
+			signature: typed_signature(self.odd_y_parity, self.r, self.s),
+			gas_limit: self.gas_limit,
+			max_fee_per_gas: self.gas_price,
+			max_priority_fee_per_gas: None,
+			value: self.value,
+			action: self.action,
+			input: &self.input,
+			intrinsic_gas: self.intrinsic_gas(fork),
+		}
+		.validate(chain_id, fork)
+	}
+}
+
+impl EIP1559Transaction {
+	/// Check the transaction for a chain with `chain_id` under the rules of `fork`, returning
+	/// every rule that is broken.
+	pub fn validate(&self, chain_id: u64, fork: Fork) -> Result<(), Vec<TransactionViolation>> {
+		TransactionFields {
+			type_id: Some(2),
+			chain_id: Some(self.chain_id),
+			signature: typed_signature(self.odd_y_parity, self.r, self.s),
+			gas_limit: self.gas_limit,
+			max_fee_per_gas: self.max_fee_per_gas,
+			max_priority_fee_per_gas: Some(self.max_priority_fee_per_gas),
+			value: self.value,
+			action: self.action,
+			input: &self.input,
+			intrinsic_gas: self.intrinsic_gas(fork),
+		}
+		.validate(chain_id, fork)
+	}
+}
+
+impl EIP4844Transaction {
+	/// Check the transaction for a chain with `chain_id` under the rules of `fork`, returning
+	/// every rule that is broken.
+	pub fn validate(&self, chain_id: u64, fork: Fork) -> Result<(), Vec<TransactionViolation>> {
+		TransactionFields {
+			type_id: Some(3),
+			chain_id: Some(self.chain_id),
+			signature: typed_signature(self.odd_y_parity, self.r, self.s),
+			gas_limit: self.gas_limit,
+			max_fee_per_gas: self.max_fee_per_gas,
+			max_priority_fee_per_gas: Some(self.max_priority_fee_per_gas),
+			value: self.value,
+			action: TransactionAction::Call(self.to),
+			input: &self.input,
+			intrinsic_gas: self.intrinsic_gas(fork),
+		}
+		.validate(chain_id, fork)
+	}
+}
+
+impl EIP7702Transaction {
+	/// Check the transaction for a chain with `chain_id` under the rules of `fork`, returning
+	/// every rule that is broken.
+	pub fn validate(&self, chain_id: u64, fork: Fork) -> Result<(), Vec<TransactionViolation>> {
+		TransactionFields {
+			type_id: Some(4),
+			chain_id: Some(self.chain_id),
+			signature: typed_signature(self.odd_y_parity, self.r, self.s),
+			gas_limit: self.gas_limit,
+			max_fee_per_gas: self.max_fee_per_gas,
+			max_priority_fee_per_gas: Some(self.max_priority_fee_per_gas),
+			value: self.value,
+			action: TransactionAction::Call(self.to),
+			input: &self.input,
+			intrinsic_gas: self.intrinsic_gas(fork),
+		}
+		.validate(chain_id, fork)
+	}
+}
+
+macro_rules! impl_enum_validate {
+	($($enum:ident { $($variant:ident),* }),*) => {
+		$(
+			impl $enum {
+				/// Check the transaction for a chain with `chain_id` under the rules of `fork`,
+				/// returning every rule that is broken.
+				pub fn validate(
+					&self,
+					chain_id: u64,
+					fork: Fork,
+				) -> Result<(), Vec<TransactionViolation>> {
+					match self {
+						$($enum::$variant(t) => t.validate(chain_id, fork),)*
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_enum_validate!(
+	TransactionV1 { Legacy, EIP2930 },
+	TransactionV2 {
+		Legacy,
+		EIP2930,
+		EIP1559
+	},
+	TransactionV3 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844
+	},
+	TransactionV4 {
+		Legacy,
+		EIP2930,
+		EIP1559,
+		EIP4844,
+		EIP7702
+	}
+);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{ForkCondition, PartialHeader};
+	use alloc::vec;
+	use ethereum_types::{Bloom, H160, H64};
+
+	fn spec() -> ChainSpec {
+		ChainSpec::new(1).with_fork(Fork::London, ForkCondition::Block(10))
+	}
+
+	fn header(number: u64, gas_limit: u64, gas_used: u64, base_fee: Option<u64>) -> Header {
+		Header::new(
+			PartialHeader {
+				parent_hash: H256::zero(),
+				beneficiary: H160::zero(),
+				state_root: H256::zero(),
+				receipts_root: H256::zero(),
+				logs_bloom: Bloom::zero(),
+				difficulty: U256::zero(),
+				number: number.into(),
+				gas_limit: gas_limit.into(),
+				gas_used: gas_used.into(),
+				timestamp: number * 12,
+				extra_data: vec![],
+				mix_hash: H256::zero(),
+				nonce: H64::zero(),
+				base_fee: base_fee.map(Into::into),
+				blob_gas_used: None,
+				excess_blob_gas: None,
+				parent_beacon_block_root: None,
+				requests_hash: None,
+			},
+			H256::zero(),
+			H256::zero(),
+		)
+	}
+
+	fn child(parent: &Header, gas_limit: u64, gas_used: u64, base_fee: Option<u64>) -> Header {
+		let mut child = header(block_number(parent) + 1, gas_limit, gas_used, base_fee);
+		child.parent_hash = parent.hash();
+		child
+	}
+
+	#[test]
+	fn accepts_valid_chain_across_london() {
+		let spec = spec();
+		let pre_london = header(9, 15_000_000, 15_000_000, None);
+		let london = child(&pre_london, 30_000_000, 30_000_000, Some(INITIAL_BASE_FEE));
+		assert_eq!(london.validate_against_parent(&pre_london, &spec), Ok(()));
+
+		// A full block raises the base fee by 12.5%.
+		let next = child(&london, 30_000_000, 0, Some(1_125_000_000));
+		assert_eq!(next.validate_against_parent(&london, &spec), Ok(()));
+
+		// An empty block lowers it by 12.5%.
+		let last = child(&next, 30_000_000, 15_000_000, Some(984_375_000));
+		assert_eq!(last.validate_against_parent(&next, &spec), Ok(()));
+	}
+
+	#[test]
+	fn reports_every_violation() {
+		let spec = spec();
+		let parent = header(20, 30_000_000, 15_000_000, Some(7));
+		let mut bad = header(22, 31_000_000, 32_000_000, Some(8));
+		bad.timestamp = parent.timestamp;
+		bad.extra_data = vec![0; 33];
+
+		assert_eq!(
+			bad.validate_against_parent(&parent, &spec),
+			Err(vec![
+				HeaderViolation::ParentHashMismatch {
+					expected: parent.hash(),
+					actual: H256::zero(),
+				},
+				HeaderViolation::NumberMismatch {
+					expected: 21.into(),
+					actual: 22.into(),
+				},
+				HeaderViolation::TimestampNotIncreasing {
+					parent: parent.timestamp,
+					actual: parent.timestamp,
+				},
+				HeaderViolation::GasLimitOutOfBounds {
+					parent: 30_000_000.into(),
+					actual: 31_000_000.into(),
+				},
+				HeaderViolation::GasUsedExceedsLimit {
+					gas_used: 32_000_000.into(),
+					gas_limit: 31_000_000.into(),
+				},
+				HeaderViolation::ExtraDataTooLong { len: 33 },
+				HeaderViolation::BaseFeeMismatch {
+					expected: 7.into(),
+					actual: 8.into(),
+				},
+			])
+		);
+
+		let pre_london = header(5, 30_000_000, 0, None);
+		assert_eq!(
+			child(&pre_london, 30_000_000, 0, Some(1)).validate_against_parent(&pre_london, &spec),
+			Err(vec![HeaderViolation::UnexpectedBaseFee])
+		);
+	}
+
+	#[test]
+	fn validates_transactions() {
+		use crate::EnvelopedDecodable;
+		use hex_literal::hex;
+
+		// Example transaction from EIP-155.
+		let bytes = hex!("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+		let tx = <TransactionV2 as EnvelopedDecodable>::decode(&bytes).unwrap();
+		assert_eq!(tx.validate(1, Fork::London), Ok(()));
+		assert_eq!(
+			tx.validate(5, Fork::London),
+			Err(vec![TransactionViolation::ChainIdMismatch {
+				expected: 5,
+				actual: 1
+			}])
+		);
+
+		let tx = EIP1559Transaction {
+			chain_id: 1,
+			nonce: 0.into(),
+			max_priority_fee_per_gas: 3.into(),
+			max_fee_per_gas: 2.into(),
+			gas_limit: U256::MAX,
+			action: TransactionAction::Create,
+			value: 0.into(),
+			input: vec![0; MAX_INITCODE_SIZE + 1],
+			access_list: vec![],
+			odd_y_parity: false,
+			r: H256::repeat_byte(0x01),
+			s: H256::repeat_byte(0x80),
+		};
+		assert_eq!(
+			TransactionV2::EIP1559(tx.clone()).validate(1, Fork::Shanghai),
+			Err(vec![
+				TransactionViolation::PriorityFeeExceedsMaxFee {
+					max_priority_fee_per_gas: 3.into(),
+					max_fee_per_gas: 2.into(),
+				},
+				TransactionViolation::HighS,
+				TransactionViolation::InitcodeTooLarge {
+					size: MAX_INITCODE_SIZE + 1
+				},
+				TransactionViolation::CostOverflow,
+			])
+		);
+		assert_eq!(
+			EIP1559Transaction {
+				gas_limit: 21_000.into(),
+				input: vec![],
+				r: H256::zero(),
+				..tx
+			}
+			.validate(1, Fork::Berlin),
+			Err(vec![
+				TransactionViolation::UnsupportedType(2),
+				TransactionViolation::IntrinsicGasTooLow {
+					gas_limit: 21_000.into(),
+					intrinsic: 53_000,
+				},
+				TransactionViolation::PriorityFeeExceedsMaxFee {
+					max_priority_fee_per_gas: 3.into(),
+					max_fee_per_gas: 2.into(),
+				},
+				TransactionViolation::InvalidSignature,
+			])
+		);
+	}
+}
@@ -0,0 +1,21 @@
+use ethereum_types::Address;
+
+/// Validator withdrawal from the consensus layer (EIP-4895).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(rlp::RlpEncodable, rlp::RlpDecodable)]
+#[cfg_attr(
+	feature = "with-codec",
+	derive(codec::Encode, codec::Decode, scale_info::TypeInfo)
+)]
+#[cfg_attr(
+	feature = "with-serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "camelCase")
+)]
+pub struct Withdrawal {
+	pub index: u64,
+	pub validator_index: u64,
+	pub address: Address,
+	/// Amount in gwei.
+	pub amount: u64,
+}